@@ -0,0 +1,31 @@
+// Parsing into an arena that is reset and reused for every document, compared with parsing
+// into an owned `Value`, which allocates every string, array, and object.
+use std::hint::black_box;
+
+use bourne::arena::Arena;
+use bourne::Value;
+
+mod common;
+
+fn main() {
+    let items: Vec<String> = (0..10_000)
+        .map(|i| format!(r#"{{"id": {i}, "name": "item {i}", "tags": ["a", "b\n"], "nested": {{"score": {i}.5, "ok": true}}}}"#))
+        .collect();
+    let source = format!("[{}]", items.join(", "));
+    common::bench("parse Value", 100, || {
+        black_box(Value::parse(black_box(&source)).unwrap());
+    });
+    let mut arena = Arena::new();
+    common::bench("parse into reused Arena", 100, || {
+        black_box(arena.parse(black_box(&source)).unwrap());
+        arena.reset();
+    });
+    let value = Value::parse(&source).unwrap();
+    common::bench("to_string Value", 100, || {
+        black_box(black_box(&value).to_string());
+    });
+    let value = arena.parse(&source).unwrap();
+    common::bench("to_string arena Value", 100, || {
+        black_box(black_box(&value).to_string());
+    });
+}
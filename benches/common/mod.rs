@@ -0,0 +1,27 @@
+// A minimal benchmark runner shared by the benchmarks, so they don't need a benchmark
+// framework. Run them with `cargo bench`.
+use std::time::{Duration, Instant};
+
+/// Run `f` `iterations` times after a warm-up, and print the average time per iteration.
+pub fn bench<F: FnMut()>(name: &str, iterations: u32, mut f: F) {
+    for _ in 0..iterations.div_ceil(10) {
+        f();
+    }
+    let start = Instant::now();
+    for _ in 0..iterations {
+        f();
+    }
+    let per_iteration = start.elapsed() / iterations;
+    println!("{name:<40} {:>12}", format_duration(per_iteration));
+}
+
+fn format_duration(duration: Duration) -> String {
+    let nanos = duration.as_nanos();
+    if nanos < 10_000 {
+        format!("{nanos} ns/iter")
+    } else if nanos < 10_000_000 {
+        format!("{} us/iter", nanos / 1_000)
+    } else {
+        format!("{} ms/iter", nanos / 1_000_000)
+    }
+}
@@ -0,0 +1,21 @@
+// Parsing bytes with `Value::from_slice`, which only validates the UTF-8 of the strings and
+// numbers it reads, compared with validating the whole input first and parsing the `&str`.
+use std::hint::black_box;
+
+use bourne::Value;
+
+mod common;
+
+fn main() {
+    let items: Vec<String> = (0..10_000)
+        .map(|i| format!(r#"{{"id": {i}, "name": "item number {i}", "tags": ["ünïcödé", "plain"], "score": {i}.5}}"#))
+        .collect();
+    let source = format!("[{}]", items.join(", ")).into_bytes();
+    common::bench("from_slice", 100, || {
+        black_box(Value::from_slice(black_box(&source)).unwrap());
+    });
+    common::bench("from_utf8 and parse", 100, || {
+        let text = std::str::from_utf8(black_box(&source)).unwrap();
+        black_box(Value::parse(text).unwrap());
+    });
+}
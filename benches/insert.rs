@@ -0,0 +1,39 @@
+// Inserting over existing keys with `&str` keys, which doesn't allocate a new key, compared
+// with `String` keys, which are always allocated.
+use std::hint::black_box;
+
+use bourne::Value;
+
+mod common;
+
+const KEYS: [&str; 8] = ["id", "name", "email", "created_at", "updated_at", "active", "score", "tags"];
+
+fn object() -> Value {
+    let mut object = Value::Null;
+    for key in KEYS {
+        object.insert(key, 0i64);
+    }
+    object
+}
+
+fn main() {
+    let mut value = object();
+    common::bench("insert &str over existing key", 1_000_000, || {
+        for (count, key) in KEYS.iter().enumerate() {
+            black_box(value.insert(black_box(*key), count as i64));
+        }
+    });
+    let mut value = object();
+    common::bench("insert String over existing key", 1_000_000, || {
+        for (count, key) in KEYS.iter().enumerate() {
+            black_box(value.insert(black_box(key.to_string()), count as i64));
+        }
+    });
+    common::bench("insert &str into new object", 100_000, || {
+        let mut value = Value::Null;
+        for (count, key) in KEYS.iter().enumerate() {
+            value.insert(black_box(*key), count as i64);
+        }
+        black_box(value);
+    });
+}
@@ -0,0 +1,22 @@
+// Writing a document made mostly of long strings without escapes, where each unescaped run
+// is written at once, compared with strings that need escaping throughout.
+use std::hint::black_box;
+
+use bourne::Value;
+
+mod common;
+
+fn document(text: &str) -> Value {
+    Value::Array((0..1000).map(|_| Value::String(text.repeat(20))).collect())
+}
+
+fn main() {
+    let plain = document("The quick brown fox jumps over the lazy dog. ");
+    let escaped = document("\"Quoted\"\tand\nescaped\\ ");
+    common::bench("to_string plain strings", 500, || {
+        black_box(black_box(&plain).to_string());
+    });
+    common::bench("to_string escaped strings", 500, || {
+        black_box(black_box(&escaped).to_string());
+    });
+}
@@ -50,7 +50,7 @@ impl Parse for Null {
 
 impl Parse for Value {
     fn parse(input: ParseStream) -> syn::Result<Self> {
-        if let Ok(_) = input.parse::<Null>() {
+        if input.parse::<Null>().is_ok() {
             Ok(Value::Null)
         } else if let Ok(Array(array)) = input.parse::<Array>() {
             Ok(Value::Array(array))
@@ -79,14 +79,36 @@ impl Parse for KeyValuePair {
 }
 
 impl Value {
-    fn to_tokenstream(self) -> proc_macro2::TokenStream {
+    /// Find string literal keys that appear more than once in the same object.
+    fn check_duplicate_keys(&self) -> Result<()> {
+        match self {
+            Value::Object(object) => {
+                let mut keys = std::collections::HashMap::new();
+                for KeyValuePair { key, value } in object {
+                    if let syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(lit), .. }) = key {
+                        if let Some(first) = keys.insert(lit.value(), lit.span()) {
+                            let mut err = syn::Error::new(lit.span(), format!("duplicate key `{}`", lit.value()));
+                            err.combine(syn::Error::new(first, "first used here"));
+                            return Err(err);
+                        }
+                    }
+                    value.check_duplicate_keys()?;
+                }
+                Ok(())
+            }
+            Value::Array(array) => array.iter().try_for_each(Value::check_duplicate_keys),
+            _ => Ok(()),
+        }
+    }
+
+    fn into_tokenstream(self) -> proc_macro2::TokenStream {
         use quote::quote;
         match self {
             Value::Null => quote!(bourne::Value::Null),
             Value::Object(object) => {
                 let capacity = object.len();
                 let inserts = object.into_iter().map(|KeyValuePair { key, value }| {
-                    let value = value.to_tokenstream();
+                    let value = value.into_tokenstream();
                     quote! { map.insert((#key).to_owned(), #value); }
                 }).collect::<Vec<_>>();
                 quote! {
@@ -100,7 +122,7 @@ impl Value {
             Value::Array(array) => {
                 let capacity = array.len();
                 let lines = array.into_iter().map(|value| {
-                    let value = value.to_tokenstream();
+                    let value = value.into_tokenstream();
                     quote!{ array.push(#value); }
                 }).collect::<Vec<_>>();
                 quote! {
@@ -120,7 +142,7 @@ impl Value {
 
 /// Create a JSON object. Expressions are allowed as values as long as the result is convertible to a Value.
 /// Example:
-/// ```rust,no_run
+/// ```rust,ignore
 /// let number = 3.14;
 /// let value = json!(
 ///     {
@@ -128,12 +150,38 @@ impl Value {
 ///     }
 /// );
 /// ```
+/// With the `deny_duplicate_keys` feature, string literal keys that appear more than once
+/// in the same object are a compile error.
 #[proc_macro]
 pub fn json(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     if input.is_empty() {
         quote::quote!{ bourne::Value::Null }.into()
     } else {
         let value = parse_macro_input!(input as Value);
-        value.to_tokenstream().into()
+        if cfg!(feature = "deny_duplicate_keys") {
+            if let Err(err) = value.check_duplicate_keys() {
+                // A block, so that the error for each occurrence is reported.
+                let errors = err.to_compile_error();
+                return quote::quote!{ { #errors } }.into();
+            }
+        }
+        value.into_tokenstream().into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn duplicate_keys_test() -> Result<()> {
+        let value = syn::parse_str::<Value>(r#"{"a": 1, "b": [{"c": 2, "c": 3}], "a": 4}"#)?;
+        let err = value.check_duplicate_keys().unwrap_err();
+        let messages = err.into_iter().map(|err| err.to_string()).collect::<Vec<_>>();
+        assert_eq!(messages, ["duplicate key `c`", "first used here"]);
+
+        let value = syn::parse_str::<Value>(r#"{"a": {"a": 1}, "b": [{"a": 2}, {"a": 3}], key: 4, key: 5}"#)?;
+        assert!(value.check_duplicate_keys().is_ok());
+        Ok(())
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,287 @@
+//! Parsing into a bump arena, where a document's strings, arrays, and objects are allocated
+//! together and freed at once, instead of one allocation each.
+//!
+//! ## Safety
+//!
+//! [Arena] hands out references into memory it owns, so the `unsafe` code here relies on
+//! these rules:
+//! * The memory is a list of chunks, each a boxed slice of [UnsafeCell] words that is
+//!   never resized or moved, so a reference into a chunk stays valid while the chunk is in
+//!   the list, even when more chunks are pushed (which only moves the boxes).
+//! * Every allocation is a new range of the last chunk, after the ranges before it and
+//!   aligned for its type, so no two allocations overlap. The words are [UnsafeCell]s, so
+//!   writing a new range through `&Arena` doesn't conflict with the shared references to
+//!   earlier ranges.
+//! * Only `Copy` types are allocated, so nothing in the arena needs to be dropped.
+//! * References from the arena borrow it, and chunks are only freed by [Arena::reset] and
+//!   dropping the arena, which both need it not to be borrowed.
+//!
+//! The arena tests are run under Miri in CI to check this.
+use std::cell::{Cell, RefCell, UnsafeCell};
+use std::mem::MaybeUninit;
+
+use crate::ValueMap;
+
+/// The memory of an [Arena] is allocated in words, so that everything in it can be aligned.
+type Word = UnsafeCell<MaybeUninit<u64>>;
+
+/// The number of words in the first chunk of an [Arena] (4 KiB).
+const FIRST_CHUNK_WORDS: usize = 512;
+/// Each chunk of an [Arena] is twice the size of the last, up to this many words (1 MiB),
+/// unless a bigger chunk is needed for a single allocation.
+const MAX_CHUNK_WORDS: usize = 1 << 17;
+
+/// A bump allocator for the strings, arrays, and objects of [arena::Value](Value)s.
+///
+/// Parsing with [Arena::parse] makes a few large allocations for the whole document instead
+/// of one for every string, array, and object, and the values are all freed at once when the
+/// arena is dropped or [reset](Arena::reset). Reusing one arena for many documents avoids
+/// allocating at all once it is big enough.
+///
+/// #### Example:
+/// ```
+/// use bourne::arena::Arena;
+///
+/// let mut arena = Arena::new();
+/// for source in [r#"{"id": 1}"#, r#"{"id": 2}"#] {
+///     let value = arena.parse(source).unwrap();
+///     assert_eq!(value.to_string(), source.replace(' ', ""));
+///     arena.reset();
+/// }
+/// ```
+#[derive(Default)]
+pub struct Arena {
+    chunks: RefCell<Vec<Box<[Word]>>>,
+    /// The number of bytes used in the last chunk.
+    used: Cell<usize>,
+}
+
+impl Arena {
+    /// Create an empty [Arena]. Nothing is allocated until it is used.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The total size of the memory allocated by the arena, in bytes.
+    pub fn allocated_bytes(&self) -> usize {
+        self.chunks.borrow().iter().map(|chunk| std::mem::size_of_val(&**chunk)).sum()
+    }
+
+    /// Free every value in the arena, keeping the largest chunk of memory to reuse.
+    pub fn reset(&mut self) {
+        let chunks = self.chunks.get_mut();
+        if let Some(largest) = (0..chunks.len()).max_by_key(|&index| chunks[index].len()) {
+            chunks.swap(0, largest);
+            chunks.truncate(1);
+        }
+        self.used.set(0);
+    }
+
+    /// Allocate `size` bytes aligned to `align`, which must not be more than the alignment
+    /// of a [Word].
+    fn alloc_bytes(&self, size: usize, align: usize) -> *mut u8 {
+        let mut chunks = self.chunks.borrow_mut();
+        let mut offset = self.used.get().next_multiple_of(align);
+        let capacity = chunks.last().map_or(0, |chunk| std::mem::size_of_val(&**chunk));
+        if offset + size > capacity {
+            let grown = (capacity / size_of::<Word>() * 2).clamp(FIRST_CHUNK_WORDS, MAX_CHUNK_WORDS);
+            let words = size.div_ceil(size_of::<Word>()).max(grown);
+            chunks.push((0..words).map(|_| UnsafeCell::new(MaybeUninit::uninit())).collect());
+            offset = 0;
+        }
+        self.used.set(offset + size);
+        let chunk = chunks.last().unwrap();
+        // SAFETY: `offset + size` is within the chunk, so the pointer stays in bounds. The
+        // memory is only reached through `UnsafeCell`s, so it can be written through a
+        // shared reference, and no other allocation overlaps it.
+        unsafe { UnsafeCell::raw_get(chunk.as_ptr()).cast::<u8>().add(offset) }
+    }
+
+    /// Copy `items` into the arena.
+    pub(crate) fn alloc_slice<T: Copy>(&self, items: &[T]) -> &[T] {
+        const { assert!(align_of::<T>() <= align_of::<Word>()) };
+        if items.is_empty() {
+            return &[];
+        }
+        let ptr = self.alloc_bytes(size_of_val(items), align_of::<T>()).cast::<T>();
+        // SAFETY: The memory is aligned for `T`, has room for `items`, and is not used by
+        // anything else. It lives as long as the arena isn't reset, which needs `&mut self`.
+        // `T` is `Copy`, so it doesn't need to be dropped.
+        unsafe {
+            ptr.copy_from_nonoverlapping(items.as_ptr(), items.len());
+            std::slice::from_raw_parts(ptr, items.len())
+        }
+    }
+
+    /// Copy `text` into the arena.
+    pub(crate) fn alloc_str(&self, text: &str) -> &str {
+        // SAFETY: The bytes were copied from a `str`, so they are valid UTF-8.
+        unsafe { std::str::from_utf8_unchecked(self.alloc_slice(text.as_bytes())) }
+    }
+}
+
+impl std::fmt::Debug for Arena {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Arena")
+            .field("allocated_bytes", &self.allocated_bytes())
+            .finish()
+    }
+}
+
+/// A JSON value whose strings, arrays, and objects are borrowed from an [Arena].
+/// See [Arena::parse].
+///
+/// Objects are slices of their entries in the order they appear in the source, with no
+/// duplicate keys (like a [Value::Object](crate::Value::Object) with the `preserve_order`
+/// feature on), so looking up a key with [Value::get] is a linear search.
+/// [Display](std::fmt::Display) writes a value exactly like the same [crate::Value].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Value<'a> {
+    Null,
+    Boolean(bool),
+    Number(Number<'a>),
+    String(&'a str),
+    Array(&'a [Value<'a>]),
+    Object(&'a [(&'a str, Value<'a>)]),
+}
+
+/// A JSON number in an [arena::Value](Value). This is like [crate::Number], but
+/// [Number::Big] is borrowed from the [Arena].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Number<'a> {
+    Float(f64),
+    Int(i64),
+    Big(&'a str),
+}
+
+impl Number<'_> {
+    /// Convert to a [crate::Number].
+    pub fn to_number(&self) -> crate::Number {
+        match *self {
+            Number::Float(float) => crate::Number::Float(float),
+            Number::Int(int) => crate::Number::Int(int),
+            Number::Big(text) => crate::Number::Big(text.to_owned()),
+        }
+    }
+}
+
+impl<'a> Value<'a> {
+    /// Get the value of `key` if this is an object that has it.
+    pub fn get(&self, key: &str) -> Option<&Value<'a>> {
+        match self {
+            Value::Object(entries) => entries.iter().find(|(entry, _)| *entry == key).map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    /// Convert to an owned [crate::Value], which doesn't borrow from the [Arena].
+    pub fn to_value(&self) -> crate::Value {
+        match *self {
+            Value::Null => crate::Value::Null,
+            Value::Boolean(boolean) => crate::Value::Boolean(boolean),
+            Value::Number(number) => crate::Value::Number(number.to_number()),
+            Value::String(string) => crate::Value::String(string.to_owned()),
+            Value::Array(array) => crate::Value::Array(array.iter().map(Value::to_value).collect()),
+            Value::Object(entries) => crate::Value::Object(entries.iter()
+                .map(|(key, value)| ((*key).to_owned(), value.to_value()))
+                .collect::<ValueMap>()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ParseError;
+    use crate::parse::{KeyNormalizer, ParseOptions};
+
+    const SOURCE: &str = r#"{
+        "name": "arena \"test\"\né",
+        "numbers": [0, -1, 2.5, 1e3, 123456789012345678901234567890],
+        "nested": {"empty": {}, "list": [[], [null, true, false]], "key": "value"},
+        "name2": "plain"
+    }"#;
+
+    #[test]
+    fn parse_test() -> Result<(), ParseError> {
+        let arena = Arena::new();
+        let options = ParseOptions {
+            preserve_big_integers: true,
+            ..Default::default()
+        };
+        let value = arena.parse_with(SOURCE, options.clone())?;
+        let owned = crate::Value::parse_with(SOURCE, options.clone())?;
+        assert_eq!(value.to_value(), owned);
+        assert_eq!(value.get("name"), Some(&Value::String("arena \"test\"\n\u{e9}")));
+        assert!(matches!(value.get("numbers"), Some(Value::Array([.., Value::Number(Number::Big("123456789012345678901234567890"))]))));
+        assert_eq!(value.get("missing"), None);
+
+        // The entries are in source order, so the output only matches when the owned value
+        // keeps its order too.
+        if cfg!(feature = "preserve_order") {
+            assert_eq!(value.to_string(), owned.to_string());
+            assert_eq!(format!("{value:#}"), format!("{owned:#}"));
+            assert_eq!(format!("{value:#2.0}"), format!("{owned:#2.0}"));
+        }
+        assert_eq!(crate::Value::parse_with(&value.to_string(), options)?, owned);
+        let inner = value.get("nested").unwrap().get("list").unwrap();
+        assert_eq!(inner.to_string(), "[[],[null,true,false]]");
+        Ok(())
+    }
+
+    #[test]
+    fn duplicate_keys_test() -> Result<(), ParseError> {
+        let arena = Arena::new();
+        let source = r#"{"a": 1, "b": {"a": 2, "a": 3}, "a": 4, "c": 5, "a": 6}"#;
+        let value = arena.parse(source)?;
+        assert_eq!(value.to_string(), r#"{"a":6,"b":{"a":3},"c":5}"#);
+        assert_eq!(value.to_value(), crate::Value::parse(source)?);
+
+        let options = ParseOptions {
+            collect_duplicate_keys: true,
+            ..Default::default()
+        };
+        let value = arena.parse_with(source, options.clone())?;
+        assert_eq!(value.to_string(), r#"{"a":[1,4,6],"b":{"a":[2,3]},"c":5}"#);
+        assert_eq!(value.to_value(), crate::Value::parse_with(source, options)?);
+
+        let options = ParseOptions {
+            key_normalizer: Some(KeyNormalizer::new(str::to_uppercase)),
+            max_object_entries: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(arena.parse_with(r#"{"a": 1, "A": 2, "b": 3}"#, options.clone())?.to_string(), r#"{"A":2,"B":3}"#);
+        assert!(matches!(arena.parse_with(r#"{"a": 1, "b": 2, "c": 3}"#, options), Err(ParseError::LimitExceeded { index: 23 })));
+        Ok(())
+    }
+
+    #[test]
+    fn errors_test() -> Result<(), ParseError> {
+        let arena = Arena::new();
+        assert!(matches!(arena.parse("[1, 2"), Err(ParseError::UnexpectedEOF)));
+        assert!(matches!(arena.parse(r#"{"a": 1} x"#), Err(ParseError::InvalidCharacter(9))));
+        assert!(matches!(arena.parse(r#"["\x"]"#), Ok(Value::Array([Value::String("x")]))));
+        let strict = ParseOptions {
+            strict_escapes: true,
+            ..Default::default()
+        };
+        assert!(matches!(arena.parse_with(r#"["\x"]"#, strict), Err(ParseError::InvalidEscapeSequence { index: 2 })));
+        let deep = format!("{}{}", "[".repeat(1000), "]".repeat(1000));
+        assert!(matches!(arena.parse(&deep), Err(ParseError::DepthLimitExceeded { index: 128 })));
+        Ok(())
+    }
+
+    #[test]
+    fn reset_test() -> Result<(), ParseError> {
+        let mut arena = Arena::new();
+        let big = format!("[{}]", vec![r#""a string that is copied into the arena""#; 1000].join(","));
+        assert_eq!(arena.parse(&big)?.to_string(), big);
+        let allocated = arena.allocated_bytes();
+        assert!(allocated > big.len());
+        arena.reset();
+        assert!(arena.allocated_bytes() <= allocated);
+        assert_eq!(arena.parse(&big)?.to_string(), big);
+        assert_eq!(arena.parse("[]")?, Value::Array(&[]));
+        Ok(())
+    }
+}
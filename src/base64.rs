@@ -0,0 +1,109 @@
+// Base64 (RFC 4648, standard alphabet with padding) for binary data in strings.
+use crate::Value;
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Encode `bytes` as padded base64.
+fn encode(bytes: &[u8]) -> String {
+    let mut output = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (i, &byte)| group | (byte as u32) << (16 - 8 * i));
+        // A chunk of n bytes produces n + 1 characters, and the rest is padding.
+        for i in 0..4 {
+            if i <= chunk.len() {
+                output.push(ALPHABET[(group >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                output.push('=');
+            }
+        }
+    }
+    output
+}
+
+/// Get the 6-bit value of a base64 character.
+fn decode_char(c: u8) -> Option<u32> {
+    Some(match c {
+        b'A'..=b'Z' => c - b'A',
+        b'a'..=b'z' => c - b'a' + 26,
+        b'0'..=b'9' => c - b'0' + 52,
+        b'+' => 62,
+        b'/' => 63,
+        _ => return None,
+    } as u32)
+}
+
+/// Decode padded base64, or return `None` if `text` is not valid base64.
+fn decode(text: &str) -> Option<Vec<u8>> {
+    let text = text.as_bytes();
+    // `is_multiple_of` would need Rust 1.87.
+    #[allow(clippy::manual_is_multiple_of)]
+    if text.len() % 4 != 0 {
+        return None;
+    }
+    let mut output = Vec::with_capacity(text.len() / 4 * 3);
+    let chunks = text.chunks(4);
+    let last = chunks.len().saturating_sub(1);
+    for (index, chunk) in chunks.enumerate() {
+        // Padding is only allowed at the end of the last chunk.
+        let padding = chunk.iter().rev().take_while(|&&c| c == b'=').count();
+        if padding > 2 || (padding > 0 && index != last) {
+            return None;
+        }
+        let group = chunk[..4 - padding].iter().enumerate().try_fold(0u32, |group, (i, &c)| {
+            Some(group | decode_char(c)? << (18 - 6 * i))
+        })?;
+        let bytes = group.to_be_bytes();
+        output.extend_from_slice(&bytes[1..4 - padding]);
+    }
+    Some(output)
+}
+
+impl Value {
+    /// Create a [Value::String] holding `bytes` encoded as padded base64 (RFC 4648, standard
+    /// alphabet), the usual way to transport binary data in JSON.
+    pub fn from_bytes_base64(bytes: &[u8]) -> Value {
+        Value::String(encode(bytes))
+    }
+
+    /// Decode a [Value::String] holding padded base64 (see [Value::from_bytes_base64]).
+    /// Returns `None` if the [Value] is not a [Value::String] or is not valid base64.
+    pub fn as_bytes_base64(&self) -> Option<Vec<u8>> {
+        match self {
+            Value::String(text) => decode(text),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base64_test() {
+        // Test vectors from RFC 4648.
+        let vectors = [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("fooba", "Zm9vYmE="),
+            ("foobar", "Zm9vYmFy"),
+        ];
+        for (bytes, text) in vectors {
+            let value = Value::from_bytes_base64(bytes.as_bytes());
+            assert!(value == text);
+            assert_eq!(value.as_bytes_base64().as_deref(), Some(bytes.as_bytes()));
+        }
+        let all: Vec<u8> = (0..=255).chain((0..=255).rev()).collect();
+        for len in 0..all.len() {
+            let value = Value::from_bytes_base64(&all[..len]);
+            assert_eq!(value.as_bytes_base64().as_deref(), Some(&all[..len]));
+        }
+        for invalid in ["Zg=", "Zg", "Z===", "Zg==Zg==", "Zm9v!A==", "Zm=v"] {
+            assert!(Value::from(invalid).as_bytes_base64().is_none(), "{invalid}");
+        }
+        assert!(Value::from(1i64).as_bytes_base64().is_none());
+    }
+}
@@ -21,9 +21,126 @@ pub enum ParseError {
     #[error("Parse Float Error: {0}")]
     ParseFloatError(#[from]std::num::ParseFloatError),
     /// Invalid escape sequence in [String].
-    #[error("Invalid escape sequence.")]
-    InvalidEscapeSequence,
+    #[error("Invalid escape sequence. Index: {index}")]
+    InvalidEscapeSequence {
+        /// The index of the backslash that starts the escape sequence.
+        index: usize,
+    },
     /// Invalid hexadecimal value.
     #[error("Invalid Hex.")]
     InvalidHex,
-}
\ No newline at end of file
+    /// A string was longer than [ParseOptions::max_string_length](crate::parse::ParseOptions::max_string_length).
+    #[error("String too long. Index: {index}")]
+    StringTooLong {
+        /// The index where the string exceeded the maximum length.
+        index: usize,
+    },
+    /// Arrays and objects were nested deeper than [ParseOptions::max_depth](crate::parse::ParseOptions::max_depth).
+    #[error("Maximum depth exceeded. Index: {index}")]
+    DepthLimitExceeded {
+        /// The index of the array or object that exceeded the maximum depth.
+        index: usize,
+    },
+    /// A number had more digits than [ParseOptions::max_number_digits](crate::parse::ParseOptions::max_number_digits).
+    #[error("Number too long. Index: {index}")]
+    NumberTooLong {
+        /// The index of the first digit past the maximum.
+        index: usize,
+    },
+    /// The source bytes were not valid UTF-8. See [Value::from_slice](crate::Value::from_slice).
+    #[error("Invalid UTF-8. Index: {index}")]
+    InvalidUtf8 {
+        /// The index of the first byte that is not valid UTF-8.
+        index: usize,
+    },
+    /// An object had more entries than [ParseOptions::max_object_entries](crate::parse::ParseOptions::max_object_entries).
+    #[error("Object entry limit exceeded. Index: {index}")]
+    LimitExceeded {
+        /// The index just after the entry that exceeded the limit.
+        index: usize,
+    },
+}
+
+/// Non-standard or suspicious syntax that was accepted while parsing.
+/// See [Value::parse_lenient](crate::Value::parse_lenient).
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum Warning {
+    /// A comma after the last element of an array or the last entry of an object.
+    #[error("Trailing comma at index {index}.")]
+    TrailingComma {
+        /// The index of the comma.
+        index: usize,
+    },
+    /// `=` or `=>` was used instead of `:` between a key and its value.
+    #[error("Non-standard key separator at index {index}.")]
+    KeySeparator {
+        /// The index of the separator.
+        index: usize,
+    },
+    /// A `0x`, `0o`, or `0b` integer literal.
+    #[error("Non-decimal integer at index {index}.")]
+    RadixInteger {
+        /// The index of the start of the number, including its sign.
+        index: usize,
+    },
+    /// A number with a leading `+` sign.
+    #[error("Leading plus sign at index {index}.")]
+    PlusSign {
+        /// The index of the sign.
+        index: usize,
+    },
+    /// A `//` or `/* */` comment.
+    #[error("Comment at index {index}.")]
+    Comment {
+        /// The index of the `/` that starts the comment.
+        index: usize,
+    },
+    /// A key that appeared more than once in the same object. The last value wins.
+    #[error("Duplicate key at {pointer}.")]
+    DuplicateKey {
+        /// A JSON Pointer to the repeated key.
+        pointer: String,
+    },
+}
+
+impl ParseError {
+    /// The byte index in the source where the error occurred, if the error has one.
+    pub fn index(&self) -> Option<usize> {
+        match *self {
+            ParseError::InvalidCharacter(index)
+            | ParseError::UnexpectedEOFWhileParsingString(index)
+            | ParseError::LineBreakWhileParsingString(index)
+            | ParseError::StringTooLong { index }
+            | ParseError::DepthLimitExceeded { index }
+            | ParseError::LimitExceeded { index }
+            | ParseError::InvalidUtf8 { index }
+            | ParseError::InvalidEscapeSequence { index }
+            | ParseError::NumberTooLong { index } => Some(index),
+            _ => None,
+        }
+    }
+
+    /// The 1-based line and column in `source` where the error occurred, if the error has an
+    /// [index](ParseError::index). See [line_column](crate::parse::line_column).
+    pub fn line_column(&self, source: &str) -> Option<(usize, usize)> {
+        self.index().map(|index| crate::parse::line_column(source, index))
+    }
+}
+
+/// A [Value](crate::Value) was not the expected variant.
+#[derive(Debug, Error)]
+#[error("Expected {expected}, found {found}.")]
+pub struct ConversionError {
+    /// The name of the expected type.
+    pub expected: &'static str,
+    /// The name of the type that was found. See [Value::type_name](crate::Value::type_name).
+    pub found: &'static str,
+}
+
+/// A [Query](crate::query::Query) could not be parsed.
+#[derive(Debug, Error)]
+#[error("Invalid query at index {index}.")]
+pub struct QueryError {
+    /// The index in the query where parsing failed.
+    pub index: usize,
+}
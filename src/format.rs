@@ -14,10 +14,26 @@ use crate::{
 };
 
 impl std::fmt::Display for Number {
+    /// Floats are always written in fixed-point notation (never with an exponent), using the
+    /// shortest digits that parse back to the same [f64]. Very large or small floats such as
+    /// `1e300` or `1e-300` are therefore written out with all of their zeros, and digits past
+    /// the precision of an [f64] are not meaningful.
+    /// Integral floats smaller than 2^63 in magnitude are written without a fractional part,
+    /// so they are read back as integers. Larger integral floats are written with a `.0` so that
+    /// they are read back as floats (`1e21` is written as `1000000000000000000000.0`).
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        match self {
-            &Number::Float(float) => write!(f, "{}", float),
-            &Number::Int(int) => write!(f, "{}", int),
+        // 2^63. Even -2^63 is written with digits (`-9223372036854776000`) that overflow.
+        const I64_LIMIT: f64 = 9223372036854775808.0;
+        match *self {
+            Number::Float(float) => {
+                write!(f, "{}", float)?;
+                if float.is_finite() && float.fract() == 0.0 && float.abs() >= I64_LIMIT {
+                    f.write_str(".0")?;
+                }
+                Ok(())
+            }
+            Number::Int(int) => write!(f, "{}", int),
+            Number::Big(ref text) => write!(f, "{}", text),
         }
     }
 }
@@ -32,15 +48,50 @@ impl std::fmt::Display for Indent {
     /// Writes an [Indent] to a [Formatter]
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         // SAFETY: Creation of valid utf-8 string from byte array of spaces/tabs.
-        const SPACES: &'static str = unsafe { str::from_utf8_unchecked(&[b' '; 256]) };
-        const TABS: &'static str = unsafe { str::from_utf8_unchecked(&[b'\t'; 256]) };
-        match self {
-            &Self::Spaces(count) => write!(f, "{}", &SPACES[..count as usize]),
-            &Self::Tabs(count) => write!(f, "{}", &TABS[..count as usize]),
+        const SPACES: &str = unsafe { str::from_utf8_unchecked(&[b' '; 256]) };
+        const TABS: &str = unsafe { str::from_utf8_unchecked(&[b'\t'; 256]) };
+        match *self {
+            Self::Spaces(count) => write!(f, "{}", &SPACES[..count as usize]),
+            Self::Tabs(count) => write!(f, "{}", &TABS[..count as usize]),
         }
     }
 }
 
+/// Options that control how a [Value] is written, independently of its layout.
+/// See [Value::compact_print_format] and [PrettyPrint::with_options].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct FormatOptions {
+    /// Escape `<`, `>`, and `&` in strings as `\u003c`, `\u003e`, and `\u0026`, so that the
+    /// output can be safely embedded in HTML, such as inside a `<script>` element.
+    pub escape_html: bool,
+    /// Leave out object entries whose value is [Value::Null]. Nulls in arrays are still written.
+    pub omit_nulls: bool,
+    /// Escape the line and paragraph separators U+2028 and U+2029 as `\u2028` and `\u2029`.
+    /// They are valid in JSON strings but not in older JavaScript string literals, which
+    /// matters when the output is embedded in a script (such as JSONP).
+    pub escape_line_separators: bool,
+    /// Write numbers with this function instead of the default [Display](std::fmt::Display)
+    /// of [Number], such as to write floats with a fixed number of decimals. The function
+    /// must write valid JSON for the output to be valid JSON.
+    pub number_formatter: Option<NumberFormatter>,
+    /// Write a space after the opening bracket and before the closing bracket of non-empty
+    /// arrays and objects that are written on one line (`[ 1,2,3 ]` instead of `[1,2,3]`).
+    pub pad_brackets: bool,
+}
+
+/// A function that writes a [Number]. See [FormatOptions::number_formatter].
+#[derive(Debug, Clone, Copy)]
+pub struct NumberFormatter(pub fn(&Number, &mut dyn Write) -> std::fmt::Result);
+
+impl PartialEq for NumberFormatter {
+    /// Compares the function addresses, which may differ for the same function.
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::fn_addr_eq(self.0, other.0)
+    }
+}
+
+impl Eq for NumberFormatter {}
+
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 struct JsonFormatter {
     /// All on the same line.
@@ -51,6 +102,8 @@ struct JsonFormatter {
     indent: Indent,
     /// Indent level. Only modify this if you know what you're doing.
     indent_level: u32,
+    /// Options that don't affect the layout.
+    options: FormatOptions,
 }
 
 struct Indentation<'a>(&'a JsonFormatter);
@@ -74,13 +127,19 @@ impl JsonFormatter {
             sameline,
             spacing,
             indent,
-            indent_level: indent_level,
+            indent_level,
+            options: FormatOptions::default(),
         }
     }
 
+    fn with_options(mut self, options: FormatOptions) -> Self {
+        self.options = options;
+        self
+    }
+
     /// Creates a copy of self where the indent level is incremented by 1.
     fn indent(&self) -> Self {
-        let mut indent = self.clone();
+        let mut indent = *self;
         indent.indent_level += 1;
         indent
     }
@@ -95,10 +154,15 @@ impl JsonFormatter {
         write!(writer, "{}", self.indentation())
     }
 
+    /// Whether to pad the inside of the brackets of a non-empty container.
+    fn pads_brackets(&self) -> bool {
+        self.sameline && self.options.pad_brackets
+    }
+
     fn write_separator<W: Write>(&self, writer: &mut W) -> std::fmt::Result {
         write!(writer, ",")?;
         if !self.sameline {
-            write!(writer, "\n")?;
+            writeln!(writer)?;
         // Are double-negatives allowed in programming? There's not no spacing here.
         } else if self.spacing {
             write!(writer, " ")?;
@@ -156,31 +220,72 @@ pub fn measure_escaped_string<S: AsRef<str>>(s: S) -> usize {
 pub fn escape_string<S: AsRef<str>>(s: S) -> String {
     let mut buffer = String::with_capacity(measure_escaped_string(s.as_ref()));
     // Writing to a String is infallible (I think), so this should never fail.
-    write_escaped_string(&mut buffer, s).unwrap();
+    write_escaped_string(&mut buffer, s, FormatOptions::default()).unwrap();
     buffer
 }
 
-fn write_escaped_string<W: Write, S: AsRef<str>>(writer: &mut W, s: S) -> std::fmt::Result {
-    s.as_ref().chars().try_for_each(|c| {
+/// Writes a character as a `\u` escape sequence.
+fn write_unicode_escape<W: Write>(writer: &mut W, c: char) -> std::fmt::Result {
+    let hex = c as u16;
+    write!(writer, "\\u")?;
+    for i in (0..4).rev() {
+        write!(writer, "{}", hex_char(hex, i, true))?;
+    }
+    Ok(())
+}
+
+fn write_escaped_string<W: Write, S: AsRef<str>>(writer: &mut W, s: S, options: FormatOptions) -> std::fmt::Result {
+    let s = s.as_ref();
+    let bytes = s.as_bytes();
+    // Every character that needs escaping starts with a byte that is never part of another
+    // character, so the string can be scanned by byte and each run of characters between
+    // escapes written all at once.
+    let mut run_start = 0;
+    for (index, &byte) in bytes.iter().enumerate() {
+        let escape = match byte {
+            b'<' | b'>' | b'&' => options.escape_html,
+            b'\\' | b'"' | 0x00..=0x1f => true,
+            // U+2028 and U+2029 are encoded as E2 80 A8 and E2 80 A9.
+            0xe2 => options.escape_line_separators && matches!(bytes.get(index + 1..index + 3), Some([0x80, 0xa8 | 0xa9])),
+            _ => false,
+        };
+        if !escape {
+            continue;
+        }
+        writer.write_str(&s[run_start..index])?;
+        // The escaped character starts at `index`, which is a character boundary.
+        let c = s[index..].chars().next().unwrap();
+        run_start = index + c.len_utf8();
         match c {
-            '\\' => write!(writer, "\\\\")?,
-            '"' => write!(writer, "\\\"")?,
-            '\u{000c}' => write!(writer, "\\f")?,
-            '\u{0008}' => write!(writer, "\\b")?,
-            '\n' => write!(writer, "\\n")?,
-            '\r' => write!(writer, "\\r")?,
-            '\t' => write!(writer, "\\t")?,
-            '\u{0000}'..='\u{001f}' => {
-                let hex = c as u16;
-                write!(writer, "\\u")?;
-                for i in (0..4).rev() {
-                    write!(writer, "{}", hex_char(hex, i, true))?;
-                }
-            }
-            _ => write!(writer, "{c}")?,
+            '\\' => writer.write_str("\\\\")?,
+            '"' => writer.write_str("\\\"")?,
+            '\u{000c}' => writer.write_str("\\f")?,
+            '\u{0008}' => writer.write_str("\\b")?,
+            '\n' => writer.write_str("\\n")?,
+            '\r' => writer.write_str("\\r")?,
+            '\t' => writer.write_str("\\t")?,
+            _ => write_unicode_escape(writer, c)?,
         }
+    }
+    writer.write_str(&s[run_start..])
+}
+
+/// A [Write] implementation that only counts the bytes written to it.
+struct ByteCounter(usize);
+
+impl Write for ByteCounter {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        self.0 += s.len();
         Ok(())
-    })
+    }
+}
+
+/// Measures the length of a [Number] after being written.
+fn measure_number(value: &Number) -> usize {
+    let mut counter = ByteCounter(0);
+    // Writing to a ByteCounter never fails.
+    write_number(&mut counter, value, FormatOptions::default()).unwrap();
+    counter.0
 }
 
 fn write_null<W: Write>(writer: &mut W) -> std::fmt::Result {
@@ -191,27 +296,51 @@ fn write_boolean<W: Write>(writer: &mut W, value: bool) -> std::fmt::Result {
     write!(writer, "{value}")
 }
 
-fn write_number<W: Write>(writer: &mut W, value: Number) -> std::fmt::Result {
+fn write_number<W: Write>(writer: &mut W, value: &Number, options: FormatOptions) -> std::fmt::Result {
+    if let Some(NumberFormatter(formatter)) = options.number_formatter {
+        return formatter(value, writer);
+    }
     write!(writer, "{value}")
 }
 
-fn write_string<W: Write>(writer: &mut W, value: &str) -> std::fmt::Result {
+fn write_string<W: Write>(writer: &mut W, value: &str, options: FormatOptions) -> std::fmt::Result {
     write!(writer, "\"")?;
-    write_escaped_string(writer, value)?;
+    write_escaped_string(writer, value, options)?;
     write!(writer, "\"")
 }
 
-fn write_array<W: Write>(writer: &mut W, array: &[Value], formatter: JsonFormatter) -> std::fmt::Result {
+/// A value that can be written by [write_value], which lets an
+/// [arena::Value](crate::arena::Value) be written exactly like a [Value].
+trait WriteValue {
+    fn is_null(&self) -> bool;
+    fn write_value<W: Write>(&self, writer: &mut W, formatter: JsonFormatter) -> std::fmt::Result;
+}
+
+impl WriteValue for Value {
+    fn is_null(&self) -> bool {
+        matches!(self, Value::Null)
+    }
+
+    fn write_value<W: Write>(&self, writer: &mut W, formatter: JsonFormatter) -> std::fmt::Result {
+        write_value(writer, self, formatter)
+    }
+}
+
+fn write_array<W: Write, T: WriteValue>(writer: &mut W, array: &[T], formatter: JsonFormatter) -> std::fmt::Result {
     write!(writer, "[")?;
     if !formatter.sameline {
-        write!(writer, "\n")?;
+        writeln!(writer)?;
+    }
+    let padded = formatter.pads_brackets() && !array.is_empty();
+    if padded {
+        write!(writer, " ")?;
     }
     let indented_formatter = formatter.indent();
     array.iter().enumerate().try_for_each(|(index, value)| {
         if !indented_formatter.sameline {
             write!(writer, "{}", indented_formatter.indentation())?;
         }
-        write_value(writer, value, indented_formatter)?;
+        value.write_value(writer, indented_formatter)?;
         // Make sure it's not the final item.
         if index + 1 != array.len() {
             indented_formatter.write_separator(writer)?;
@@ -219,40 +348,56 @@ fn write_array<W: Write>(writer: &mut W, array: &[Value], formatter: JsonFormatt
         Ok(())
     })?;
     if !formatter.sameline {
-        write!(writer, "\n")?;
+        writeln!(writer)?;
         write!(writer, "{}", formatter.indentation())?;
     }
-    
+    if padded {
+        write!(writer, " ")?;
+    }
     write!(writer, "]")
 }
 
-fn write_object<W: Write>(writer: &mut W, object: &ValueMap, formatter: JsonFormatter) -> std::fmt::Result {
+fn write_object<'v, W, T, I>(writer: &mut W, object: I, formatter: JsonFormatter) -> std::fmt::Result
+where
+    W: Write,
+    T: WriteValue + 'v,
+    I: Iterator<Item = (&'v str, &'v T)>,
+{
     write!(writer, "{{")?;
     if !formatter.sameline {
-        write!(writer, "\n")?;
+        writeln!(writer)?;
     }
     let indent = formatter.indent();
-    object.iter().enumerate().try_for_each(|(index, (key, value))| {
+    let omit_nulls = formatter.options.omit_nulls;
+    let entries = object.filter(|(_, value)| !(omit_nulls && value.is_null()));
+    let mut written = 0;
+    entries.enumerate().try_for_each(|(index, (key, value))| {
+        // Separators are written before every item except the first, since
+        // omitted entries make it hard to know which item is the final one.
+        if index != 0 {
+            indent.write_separator(writer)?;
+        } else if formatter.pads_brackets() {
+            write!(writer, " ")?;
+        }
+        written += 1;
         if !indent.sameline {
             write!(writer, "{}", indent.indentation())?;
         }
-        write_string(writer, key)?;
+        write_string(writer, key, indent.options)?;
         if indent.spacing {
             write!(writer, " : ")?;
         } else {
             write!(writer, ":")?;
         }
-        write_value(writer, value, indent)?;
-        // Make sure it's not the final item
-        if index + 1 != object.len() {
-            indent.write_separator(writer)?;
-        }
-        Ok(())
+        value.write_value(writer, indent)
     })?;
     if !formatter.sameline {
-        write!(writer, "\n")?;
+        writeln!(writer)?;
         write!(writer, "{}", formatter.indentation())?;
     }
+    if formatter.pads_brackets() && written != 0 {
+        write!(writer, " ")?;
+    }
     write!(writer, "}}")
 }
 
@@ -260,39 +405,552 @@ fn write_value<W: Write>(writer: &mut W, value: &Value, formatter: JsonFormatter
     match value {
         Value::Null => write_null(writer),
         &Value::Boolean(boolean) => write_boolean(writer, boolean),
-        &Value::Number(number) => write_number(writer, number),
-        Value::String(string) => write_string(writer, string),
+        Value::Number(number) => write_number(writer, number, formatter.options),
+        Value::String(string) => write_string(writer, string, formatter.options),
         Value::Array(array) => write_array(writer, array, formatter),
-        Value::Object(object) => write_object(writer, object, formatter),
+        Value::Object(object) => write_object(writer, object.iter().map(|(key, value)| (key.as_str(), value)), formatter),
+    }
+}
+
+/// Write `value` in the layout selected by the flags of `f`, as described for the
+/// [Display](std::fmt::Display) implementation of [Value].
+fn display_value<T: WriteValue>(value: &T, f: &mut Formatter<'_>) -> std::fmt::Result {
+    if f.alternate() {
+        let indent = Indent::Spaces(f.width().map_or(4, |width| width.min(u8::MAX as usize) as u8));
+        let spacing = f.precision() != Some(0);
+        value.write_value(f, JsonFormatter::new(false, spacing, indent))
+    } else {
+        value.write_value(f, JsonFormatter::new(true, false, Indent::Spaces(0)))
     }
 }
 
 impl std::fmt::Display for Value {
+    /// Writes the compact form of the [Value]. The alternate flag (`{:#}`) selects the layout
+    /// of [Value::pretty_print] instead, where the width sets the number of spaces per indent
+    /// (`{:#2}`) and a precision of zero removes the spaces around colons (`{:#.0}`).
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write_value(f, self, JsonFormatter::new(true, false, Indent::Spaces(0)))
+        display_value(self, f)
     }
 }
 
-pub struct PrettyPrint<'a>(&'a Value, Indent, bool);
+#[cfg(feature = "arena")]
+impl WriteValue for crate::arena::Value<'_> {
+    fn is_null(&self) -> bool {
+        matches!(self, crate::arena::Value::Null)
+    }
+
+    fn write_value<W: Write>(&self, writer: &mut W, formatter: JsonFormatter) -> std::fmt::Result {
+        use crate::arena::Value as ArenaValue;
+        match *self {
+            ArenaValue::Null => write_null(writer),
+            ArenaValue::Boolean(boolean) => write_boolean(writer, boolean),
+            // A big integer is only converted if the formatter needs a [Number].
+            ArenaValue::Number(crate::arena::Number::Big(text)) if formatter.options.number_formatter.is_none() => writer.write_str(text),
+            ArenaValue::Number(number) => write_number(writer, &number.to_number(), formatter.options),
+            ArenaValue::String(string) => write_string(writer, string, formatter.options),
+            ArenaValue::Array(array) => write_array(writer, array, formatter),
+            ArenaValue::Object(object) => write_object(writer, object.iter().map(|(key, value)| (*key, value)), formatter),
+        }
+    }
+}
+
+#[cfg(feature = "arena")]
+impl std::fmt::Display for crate::arena::Value<'_> {
+    /// Writes the value exactly like the same [Value], including the layouts selected with
+    /// the alternate flag.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        display_value(self, f)
+    }
+}
+
+pub struct PrettyPrint<'a>(&'a Value, Indent, bool, FormatOptions);
+
+impl<'a> PrettyPrint<'a> {
+    /// Use the given [FormatOptions] when pretty printing.
+    pub fn with_options(self, options: FormatOptions) -> Self {
+        PrettyPrint(self.0, self.1, self.2, options)
+    }
+}
 
 impl<'a> std::fmt::Display for PrettyPrint<'a> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write_value(f, self.0, JsonFormatter::new(false, self.2, self.1))
+        write_value(f, self.0, JsonFormatter::new(false, self.2, self.1).with_options(self.3))
+    }
+}
+
+/// Compact printing with [FormatOptions]. See [Value::compact_print_format].
+pub struct CompactPrint<'a>(&'a Value, FormatOptions);
+
+impl<'a> std::fmt::Display for CompactPrint<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write_value(f, self.0, JsonFormatter::new(true, false, Indent::Spaces(0)).with_options(self.1))
+    }
+}
+
+/// The layout of the text written by [Value::to_string_styled].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Style {
+    /// Everything on one line without spaces, like `to_string()`: `{"a":[1,2]}`.
+    Compact,
+    /// Everything on one line, with spaces after commas and around colons: `{"a" : [1, 2]}`.
+    CompactSpaced,
+    /// One element or entry per line, like [Value::pretty_print_format].
+    Pretty {
+        /// The indentation of each level.
+        indent: Indent,
+        /// Whether or not there are spaces around colons.
+        spacing: bool,
+    },
+}
+
+impl Style {
+    /// The [JsonFormatter] that writes this style.
+    fn formatter(self) -> JsonFormatter {
+        match self {
+            Style::Compact => JsonFormatter::new(true, false, Indent::Spaces(0)),
+            Style::CompactSpaced => JsonFormatter::new(true, true, Indent::Spaces(0)),
+            Style::Pretty { indent, spacing } => JsonFormatter::new(false, spacing, indent),
+        }
+    }
+}
+
+/// Compact printing of an optional [Value], where `None` is written as `null`.
+/// See [Value::display_opt].
+pub struct DisplayOpt<'a>(Option<&'a Value>);
+
+impl<'a> std::fmt::Display for DisplayOpt<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Some(value) => write!(f, "{value}"),
+            None => write_null(f),
+        }
     }
 }
 
 impl Value {
+    /// Returns an object that displays `value` compactly, or `null` if it is `None`, such as
+    /// to log the result of [Value::pointer] without matching on it.
+    pub fn display_opt(value: Option<&Value>) -> DisplayOpt<'_> {
+        DisplayOpt(value)
+    }
 
     /// Returns an object suitable for pretty printing.
     /// #### Arguments:
     /// - `indent`: Controls the indentation. Use `Indent::Spaces(0)` if you don't want indentation (This defeats the purpose of pretty printing).
     /// - `spacing`: Determines whether or not there are spaces before and after colons.
     pub fn pretty_print_format(&self, indent: Indent, spacing: bool) -> PrettyPrint<'_> {
-        PrettyPrint(self, indent, spacing)
+        PrettyPrint(self, indent, spacing, FormatOptions::default())
+    }
+
+    /// Returns an object suitable for compact printing (the same layout as `to_string()`)
+    /// with the given [FormatOptions].
+    pub fn compact_print_format(&self, options: FormatOptions) -> CompactPrint<'_> {
+        CompactPrint(self, options)
+    }
+
+    /// Computes the exact length in bytes of the compact serialization of this [Value]
+    /// (the result of `to_string()`), which is useful to pre-allocate an output buffer.
+    pub fn serialized_len(&self) -> usize {
+        // Separators between elements.
+        fn commas(count: usize) -> usize {
+            count.saturating_sub(1)
+        }
+        match self {
+            Value::Null => 4,
+            Value::Boolean(true) => 4,
+            Value::Boolean(false) => 5,
+            Value::Number(number) => measure_number(number),
+            Value::String(string) => measure_escaped_string(string) + 2,
+            Value::Array(array) => {
+                2 + commas(array.len()) + array.iter().map(Value::serialized_len).sum::<usize>()
+            }
+            Value::Object(object) => {
+                // Each entry is a quoted key, a colon, and a value.
+                2 + commas(object.len()) + object.iter().map(|(key, value)| {
+                    measure_escaped_string(key) + 3 + value.serialized_len()
+                }).sum::<usize>()
+            }
+        }
+    }
+
+    /// Compute the length in bytes of the compact serialization, like [Value::serialized_len],
+    /// but give up and return `None` as soon as it exceeds `budget`. This can be used to
+    /// refuse to serialize very large generated documents (such as ones made of many clones
+    /// of the same subtree) without walking all of them.
+    pub fn estimate_serialized_len(&self, budget: usize) -> Option<usize> {
+        fn measure(value: &Value, total: &mut usize, budget: usize) -> Option<()> {
+            let mut add = |len: usize| {
+                *total += len;
+                (*total <= budget).then_some(())
+            };
+            match value {
+                Value::Array(array) => {
+                    add(2 + array.len().saturating_sub(1))?;
+                    array.iter().try_for_each(|value| measure(value, total, budget))
+                }
+                Value::Object(object) => {
+                    add(2 + object.len().saturating_sub(1))?;
+                    object.iter().try_for_each(|(key, value)| {
+                        *total += measure_escaped_string(key) + 3;
+                        (*total <= budget).then_some(())?;
+                        measure(value, total, budget)
+                    })
+                }
+                leaf => add(leaf.serialized_len()),
+            }
+        }
+        let mut total = 0;
+        measure(self, &mut total, budget)?;
+        Some(total)
+    }
+
+    /// Serialize this [Value] with the given [Style]. This is a single entry point for the
+    /// layouts of `to_string()`, [Value::pretty_print], and [Value::pretty_print_format].
+    pub fn to_string_styled(&self, style: Style) -> String {
+        let mut output = String::new();
+        // Writing to a String never fails.
+        write_value(&mut output, self, style.formatter()).unwrap();
+        output
+    }
+
+    /// Serialize compactly, like `to_string()`, with a leading UTF-8 byte order mark
+    /// (`\u{FEFF}`) for consumers that require one.
+    pub fn to_string_with_bom(&self) -> String {
+        let mut output = String::with_capacity('\u{feff}'.len_utf8() + self.serialized_len());
+        output.push('\u{feff}');
+        // Writing to a String never fails.
+        write!(output, "{self}").unwrap();
+        output
+    }
+
+    /// Serialize compactly the [Value] at the JSON Pointer `pointer` straight into `writer`,
+    /// without cloning the subtree first.
+    /// Returns `Ok(false)` (and writes nothing) if there is no value at `pointer`.
+    pub fn write_pointer_to<W: std::io::Write>(&self, pointer: &str, writer: &mut W) -> std::io::Result<bool> {
+        let Some(value) = self.pointer(pointer) else {
+            return Ok(false);
+        };
+        writer.write_fmt(format_args!("{value}"))?;
+        Ok(true)
     }
 
     /// Returns the default pretty printer.
     pub fn pretty_print(&self) -> PrettyPrint<'_> {
-        PrettyPrint(self, Indent::Spaces(4), true)
+        PrettyPrint(self, Indent::Spaces(4), true, FormatOptions::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialized_len_test() -> Result<(), ParseError> {
+        let documents = [
+            "null",
+            "false",
+            "-12345",
+            "0.1",
+            "1e300",
+            r#""tab\tquote\"unicode\u0001ünï""#,
+            "[]",
+            "{}",
+            r#"[1, [2, [3]], {"a": null}, true]"#,
+            r#"{"name": "Fred", "tags": ["a", "b\n"], "nested": {"x": 1.5, "y": {}}}"#,
+        ];
+        for document in documents {
+            let value = Value::from_str(document)?;
+            assert_eq!(value.serialized_len(), value.to_string().len(), "{document}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn escape_html_test() {
+        let value = Value::from("<b>&</b>");
+        let options = FormatOptions {
+            escape_html: true,
+            ..Default::default()
+        };
+        assert_eq!(value.to_string(), r#""<b>&</b>""#);
+        assert_eq!(
+            value.compact_print_format(options).to_string(),
+            r#""\u003cb\u003e\u0026\u003c/b\u003e""#,
+        );
+        let mut object = Value::Null;
+        object.insert("<key>", value);
+        assert_eq!(
+            object.pretty_print().with_options(options).to_string(),
+            "{\n    \"\\u003ckey\\u003e\" : \"\\u003cb\\u003e\\u0026\\u003c/b\\u003e\"\n}",
+        );
+    }
+
+    #[test]
+    fn fixed_point_float_test() -> Result<(), ParseError> {
+        assert_eq!(Value::from(1e21).to_string(), "1000000000000000000000.0");
+        assert_eq!(Value::from(1.5e-7).to_string(), "0.00000015");
+        let value = Value::from_str("[1e21, -2.5E+22, 1e-300]")?;
+        let text = value.to_string();
+        assert!(!text.contains(['e', 'E']), "{text}");
+        assert!(text.starts_with("[1000000000000000000000.0,-25000000000000000000000.0,0.000"), "{text}");
+        Ok(())
+    }
+
+    #[test]
+    fn integral_float_round_trip_test() -> Result<(), ParseError> {
+        // 2^63 is the first integral float that doesn't fit in an i64.
+        let floats = [1e21, -2.5e22, 1e300, 9223372036854775808.0, -9223372036854775808.0, 3.0, -0.0];
+        for float in floats {
+            let text = Value::from(float).to_string();
+            let value = Value::from_str(&text)?;
+            assert!(matches!(value, Value::Number(ref number) if number.as_f64() == float), "{text}");
+        }
+        assert_eq!(Value::from(9223372036854775808.0).to_string(), "9223372036854776000.0");
+        assert_eq!(Value::from(-9223372036854775808.0).to_string(), "-9223372036854776000.0");
+        assert_eq!(Value::from(3.0).to_string(), "3");
+        Ok(())
+    }
+
+    #[test]
+    fn omit_nulls_test() -> Result<(), ParseError> {
+        let value = Value::from_str(r#"{"a": null, "b": [null, {"c": null}], "d": {"e": null}}"#)?;
+        let options = FormatOptions {
+            omit_nulls: true,
+            ..Default::default()
+        };
+        let omitted = value.compact_print_format(options).to_string();
+        assert_eq!(Value::from_str(&omitted)?, Value::from_str(r#"{"b": [null, {}], "d": {}}"#)?);
+        assert!(!omitted.contains(",,") && !omitted.contains("{,") && !omitted.contains(",}"), "{omitted}");
+        assert_eq!(Value::from_str(&value.to_string())?, value);
+        assert_eq!(value.compact_print_format(FormatOptions::default()).to_string(), value.to_string());
+
+        let mut object = Value::Null;
+        object.insert("a", Value::Null);
+        object.insert("b", 1i64);
+        object.insert("c", Value::Null);
+        assert_eq!(object.compact_print_format(options).to_string(), r#"{"b":1}"#);
+        assert_eq!(object.pretty_print().with_options(options).to_string(), "{\n    \"b\" : 1\n}");
+        Ok(())
+    }
+
+    #[test]
+    fn display_flags_test() -> Result<(), ParseError> {
+        let value = Value::from_str(r#"{"a": [1, true]}"#)?;
+        assert_eq!(format!("{value}"), r#"{"a":[1,true]}"#);
+        assert_eq!(format!("{value:#}"), value.pretty_print().to_string());
+        assert_eq!(format!("{value:#}"), "{\n    \"a\" : [\n        1,\n        true\n    ]\n}");
+        assert_eq!(format!("{value:#2}"), "{\n  \"a\" : [\n    1,\n    true\n  ]\n}");
+        assert_eq!(format!("{value:#2.0}"), value.pretty_print_format(Indent::Spaces(2), false).to_string());
+        let width = 3;
+        assert_eq!(format!("{value:#width$}"), value.pretty_print_format(Indent::Spaces(3), true).to_string());
+        assert_eq!(format!("<{:#}>", Value::Null), "<null>");
+        Ok(())
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn escape_round_trip_test() -> Result<(), ParseError> {
+        let strings = [
+            "ends in \\",
+            "ends in two \\\\",
+            "\\",
+            "\\\"",
+            "escaped slash \\/ and \\\\/",
+            "\\n is not a newline",
+            "mixed \"quotes\", \\u0041, \t\r\n\u{0008}\u{000c}\u{0000}\u{001f} <&> é 🦀 \\",
+        ];
+        let html = FormatOptions {
+            escape_html: true,
+            ..Default::default()
+        };
+        for string in strings {
+            for options in [FormatOptions::default(), html] {
+                let mut escaped = String::new();
+                write_escaped_string(&mut escaped, string, options).unwrap();
+                assert_eq!(crate::parse::unescape_string(&escaped)?, string, "{escaped}");
+                let mut quoted = String::new();
+                write_string(&mut quoted, string, options).unwrap();
+                assert!(Value::from_str(&quoted)? == string, "{quoted}");
+            }
+        }
+        // `\/` is a valid escape for `/`, which is written back unescaped.
+        let value = Value::from_str(r#""a\/b\\""#)?;
+        assert!(value == "a/b\\");
+        assert_eq!(value.to_string(), r#""a/b\\""#);
+        Ok(())
+    }
+
+    /// The original character by character escaping, to check the output of [write_escaped_string].
+    fn write_escaped_string_by_char(writer: &mut String, s: &str, options: FormatOptions) -> std::fmt::Result {
+        s.chars().try_for_each(|c| {
+            match c {
+                '<' | '>' | '&' if options.escape_html => write_unicode_escape(writer, c)?,
+                '\\' => write!(writer, "\\\\")?,
+                '"' => write!(writer, "\\\"")?,
+                '\u{000c}' => write!(writer, "\\f")?,
+                '\u{0008}' => write!(writer, "\\b")?,
+                '\n' => write!(writer, "\\n")?,
+                '\r' => write!(writer, "\\r")?,
+                '\t' => write!(writer, "\\t")?,
+                '\u{0000}'..='\u{001f}' => write_unicode_escape(writer, c)?,
+                _ => write!(writer, "{c}")?,
+            }
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn escape_fast_path_test() {
+        let html = FormatOptions {
+            escape_html: true,
+            ..Default::default()
+        };
+        let mut strings: Vec<String> = [
+            "",
+            "plain",
+            "\"",
+            "\\",
+            "<a href=\"x\">&amp;</a>",
+            "tab\tnew\nline\r\u{0000}\u{001f}\u{007f}",
+            "é🦀\"ü\\ß\n",
+        ].into_iter().map(String::from).collect();
+        strings.push("a long plain string ".repeat(1000));
+        strings.push((0u32..0x800).filter_map(char::from_u32).collect());
+        for string in &strings {
+            for options in [FormatOptions::default(), html] {
+                let (mut fast, mut by_char) = (String::new(), String::new());
+                write_escaped_string(&mut fast, string, options).unwrap();
+                write_escaped_string_by_char(&mut by_char, string, options).unwrap();
+                assert_eq!(fast, by_char);
+            }
+            assert_eq!(escape_string(string).len(), measure_escaped_string(string));
+        }
+    }
+
+    #[test]
+    fn to_string_with_bom_test() -> Result<(), ParseError> {
+        let value = Value::from_str(r#"{"a": [1, "b"]}"#)?;
+        let output = value.to_string_with_bom();
+        assert!(output.as_bytes().starts_with(&[0xEF, 0xBB, 0xBF]));
+        assert_eq!(&output[3..], value.to_string());
+        assert_eq!(Value::from_str(&output)?, value);
+        assert!(matches!(Value::from_str("\u{feff} x"), Err(ParseError::InvalidCharacter(4))));
+        Ok(())
+    }
+
+    #[test]
+    fn pad_brackets_test() -> Result<(), ParseError> {
+        let value = Value::from_str(r#"{"list": [1, 2, 3], "empty": [], "none": {}, "skip": null}"#)?;
+        let padded = FormatOptions {
+            pad_brackets: true,
+            ..Default::default()
+        };
+        assert_eq!(Value::from_str("[1, 2, 3]")?.compact_print_format(padded).to_string(), "[ 1,2,3 ]");
+        assert_eq!(Value::from_str("[1, 2, 3]")?.compact_print_format(FormatOptions::default()).to_string(), "[1,2,3]");
+        assert_eq!(Value::from_str(r#"{"a": [1]}"#)?.compact_print_format(padded).to_string(), r#"{ "a":[ 1 ] }"#);
+        assert_eq!(Value::from_str(r#"{"a": [1]}"#)?.to_string(), r#"{"a":[1]}"#);
+        assert_eq!(Value::from_str("[[], {}]")?.compact_print_format(padded).to_string(), "[ [],{} ]");
+
+        // Objects left empty by omitted nulls are not padded.
+        let options = FormatOptions {
+            omit_nulls: true,
+            ..padded
+        };
+        assert_eq!(Value::from_str(r#"{"a": null}"#)?.compact_print_format(options).to_string(), "{}");
+
+        // Multi-line containers are not affected.
+        let pretty = value.pretty_print().with_options(padded).to_string();
+        assert_eq!(pretty, value.pretty_print().to_string());
+        Ok(())
+    }
+
+    #[test]
+    fn to_string_styled_test() -> Result<(), ParseError> {
+        let value = Value::from_str(r#"{"a": [1, 2]}"#)?;
+        assert_eq!(value.to_string_styled(Style::Compact), r#"{"a":[1,2]}"#);
+        assert_eq!(value.to_string_styled(Style::Compact), value.to_string());
+        assert_eq!(value.to_string_styled(Style::CompactSpaced), r#"{"a" : [1, 2]}"#);
+        let pretty = Style::Pretty {
+            indent: Indent::Spaces(2),
+            spacing: false,
+        };
+        assert_eq!(value.to_string_styled(pretty), "{\n  \"a\":[\n    1,\n    2\n  ]\n}");
+        assert_eq!(value.to_string_styled(pretty), value.pretty_print_format(Indent::Spaces(2), false).to_string());
+        let default = Style::Pretty {
+            indent: Indent::Spaces(4),
+            spacing: true,
+        };
+        assert_eq!(value.to_string_styled(default), value.pretty_print().to_string());
+        Ok(())
+    }
+
+    #[test]
+    fn write_pointer_to_test() -> Result<(), ParseError> {
+        let value = Value::from_str(r#"{"a": {"b": [1, {"c": "d"}]}, "e": null}"#)?;
+        let mut buffer = Vec::new();
+        assert!(value.write_pointer_to("/a/b", &mut buffer).unwrap());
+        assert_eq!(buffer, br#"[1,{"c":"d"}]"#);
+
+        let mut buffer = Vec::new();
+        assert!(!value.write_pointer_to("/a/missing", &mut buffer).unwrap());
+        assert!(buffer.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn display_opt_test() -> Result<(), ParseError> {
+        let value = Value::from_str(r#"{"a": {"b": [1, "c"]}}"#)?;
+        assert_eq!(Value::display_opt(value.pointer("/a")).to_string(), r#"{"b":[1,"c"]}"#);
+        assert_eq!(Value::display_opt(value.pointer("/missing")).to_string(), "null");
+        assert_eq!(format!("b = {}", Value::display_opt(value.pointer("/a/b/1"))), r#"b = "c""#);
+        Ok(())
+    }
+
+    #[test]
+    fn escape_line_separators_test() -> Result<(), ParseError> {
+        let value = Value::from("a\u{2028}b\u{2029}c\u{2027}\u{202a}");
+        let options = FormatOptions {
+            escape_line_separators: true,
+            ..Default::default()
+        };
+        let escaped = value.compact_print_format(options).to_string();
+        assert_eq!(escaped, "\"a\\u2028b\\u2029c\u{2027}\u{202a}\"");
+        assert_eq!(Value::from_str(&escaped)?, value);
+        assert_eq!(value.to_string(), "\"a\u{2028}b\u{2029}c\u{2027}\u{202a}\"");
+        Ok(())
+    }
+
+    #[test]
+    fn estimate_serialized_len_test() -> Result<(), ParseError> {
+        let value = Value::from_str(r#"{"a": [1, "two", null], "b\n": {"c": true}}"#)?;
+        let len = value.serialized_len();
+        assert_eq!(value.estimate_serialized_len(len), Some(len));
+        assert_eq!(value.estimate_serialized_len(len - 1), None);
+
+        // Each level doubles the size of the document: 2^16 leaves.
+        let mut huge = Value::from_str(r#"{"leaf": "some text"}"#)?;
+        for _ in 0..16 {
+            huge = Value::Array(vec![huge.clone(), huge]);
+        }
+        assert_eq!(huge.estimate_serialized_len(10_000), None);
+        Ok(())
+    }
+
+    #[test]
+    fn number_formatter_test() -> Result<(), ParseError> {
+        fn three_decimals(number: &Number, writer: &mut dyn Write) -> std::fmt::Result {
+            match *number {
+                Number::Float(float) => write!(writer, "{float:.3}"),
+                _ => write!(writer, "{number}"),
+            }
+        }
+        let options = FormatOptions {
+            number_formatter: Some(NumberFormatter(three_decimals)),
+            ..Default::default()
+        };
+        let value = Value::from_str(r#"{"a": [1.5, 2, 0.12345, -3.0]}"#)?;
+        assert_eq!(value.compact_print_format(options).to_string(), r#"{"a":[1.500,2,0.123,-3.000]}"#);
+        assert_eq!(value.pretty_print_format(Indent::Spaces(0), false).with_options(options).to_string(), "{\n\"a\":[\n1.500,\n2,\n0.123,\n-3.000\n]\n}");
+        assert_eq!(value.to_string(), r#"{"a":[1.5,2,0.12345,-3]}"#);
+        Ok(())
+    }
+}
@@ -0,0 +1,142 @@
+// Generating values from raw bytes, for fuzzing and property tests.
+use crate::{Number, Value, ValueMap};
+
+/// The maximum number of arrays and objects nested inside each other in a generated [Value].
+pub const MAX_DEPTH: usize = 4;
+/// The maximum number of elements or entries in a generated array or object.
+pub const MAX_LEN: usize = 8;
+/// The maximum number of characters in a generated string or key.
+const MAX_STRING_LEN: usize = 16;
+
+/// Reads the choices for generating a [Value] from the front of a byte slice.
+/// Once the bytes run out, every read gives zero.
+struct Generator<'a> {
+    data: &'a [u8],
+}
+
+impl Generator<'_> {
+    fn byte(&mut self) -> u8 {
+        let Some((&first, rest)) = self.data.split_first() else {
+            return 0;
+        };
+        self.data = rest;
+        first
+    }
+
+    fn u64(&mut self) -> u64 {
+        (0..8).fold(0, |int, _| int << 8 | self.byte() as u64)
+    }
+
+    /// A length up to `max`, which is zero once the bytes have run out, so that generating
+    /// always ends.
+    fn len(&mut self, max: usize) -> usize {
+        self.byte() as usize % (max + 1)
+    }
+
+    fn string(&mut self) -> String {
+        let len = self.len(MAX_STRING_LEN);
+        (0..len).map(|_| {
+            // Mostly ASCII, including the characters that need escaping.
+            match self.byte() {
+                0..=191 => (self.byte() & 0x7f) as char,
+                _ => char::from_u32((self.u64() % 0x110000) as u32).unwrap_or('\u{fffd}'),
+            }
+        }).collect()
+    }
+
+    fn number(&mut self) -> Number {
+        match self.byte() % 3 {
+            0 => Number::Int(self.u64() as i64),
+            1 => {
+                let float = f64::from_bits(self.u64());
+                // `NaN` and infinities can't be written as JSON.
+                Number::Float(if float.is_finite() { float } else { 0.5 })
+            }
+            _ => {
+                // At least 20 digits without leading zeros, so it doesn't fit in an i64.
+                let mut text = String::from(if self.byte() % 2 == 1 { "-" } else { "" });
+                let len = 20 + self.len(10);
+                text.push((b'1' + self.byte() % 9) as char);
+                text.extend((1..len).map(|_| (b'0' + self.byte() % 10) as char));
+                Number::Big(text)
+            }
+        }
+    }
+
+    fn value(&mut self, depth: usize) -> Value {
+        let containers = if depth < MAX_DEPTH { 6 } else { 4 };
+        match self.byte() % containers {
+            0 => Value::Null,
+            1 => Value::Boolean(self.byte() % 2 == 1),
+            2 => Value::Number(self.number()),
+            3 => Value::String(self.string()),
+            4 => {
+                let len = self.len(MAX_LEN);
+                Value::Array((0..len).map(|_| self.value(depth + 1)).collect())
+            }
+            _ => {
+                let len = self.len(MAX_LEN);
+                Value::Object((0..len).map(|_| (self.string(), self.value(depth + 1))).collect::<ValueMap>())
+            }
+        }
+    }
+}
+
+impl Value {
+    /// Generate a [Value] from raw bytes, such as the input of a fuzzer, so that
+    /// fuzz targets and property tests can exercise parsing and writing with every kind of
+    /// value. The same bytes always give the same value.
+    ///
+    /// Arrays and objects are nested at most [MAX_DEPTH] deep and have at most
+    /// [MAX_LEN] elements, so the value stays small. Floats are always finite, and
+    /// [Number::Big] is only used for integers that don't fit in an [i64], so the written
+    /// value parses back to an equal [Value] with
+    /// [ParseOptions::preserve_big_integers](crate::parse::ParseOptions::preserve_big_integers).
+    pub fn generate(data: &[u8]) -> Value {
+        Generator { data }.value(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ParseError;
+    use crate::parse::ParseOptions;
+
+    /// Bytes from a simple pseudo-random generator, so the test doesn't need a dependency.
+    fn bytes(seed: u64, len: usize) -> Vec<u8> {
+        let mut state = seed;
+        (0..len).map(|_| {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (state >> 56) as u8
+        }).collect()
+    }
+
+    fn depth(value: &Value) -> usize {
+        match value {
+            Value::Array(array) => 1 + array.iter().map(depth).max().unwrap_or(0),
+            Value::Object(object) => 1 + object.values().map(depth).max().unwrap_or(0),
+            _ => 0,
+        }
+    }
+
+    #[test]
+    fn generate_round_trip_test() -> Result<(), ParseError> {
+        let options = ParseOptions {
+            preserve_big_integers: true,
+            ..Default::default()
+        };
+        let mut deepest = 0;
+        for seed in 0..200 {
+            let data = bytes(seed, 512);
+            let value = Value::generate(&data);
+            assert_eq!(value, Value::generate(&data));
+            deepest = deepest.max(depth(&value));
+            assert_eq!(Value::parse_with(&value.to_string(), options.clone())?, value);
+            assert_eq!(Value::parse_with(&format!("{value:#}"), options.clone())?, value);
+        }
+        assert_eq!(deepest, MAX_DEPTH);
+        assert_eq!(Value::generate(&[]), Value::Null);
+        Ok(())
+    }
+}
@@ -1,8 +1,23 @@
 pub mod error;
 pub mod parse;
 pub mod format;
+mod number;
+pub mod pointer;
+pub mod query;
+mod merge;
+mod transform;
+mod walk;
+mod table;
+#[cfg(feature = "base64")]
+mod base64;
+#[cfg(feature = "arena")]
+pub mod arena;
+#[cfg(feature = "generate")]
+pub mod generate;
 pub use bournemacro::json;
 
+use error::ConversionError;
+
 /// The Mapping that [Value] uses for [Value::Object].  
 /// Uses [hashbrown::HashMap].
 #[cfg(not(feature = "preserve_order"))]
@@ -12,36 +27,55 @@ pub type ValueMap = hashbrown::HashMap<String, Value>;
 #[cfg(feature = "preserve_order")]
 pub type ValueMap = indexmap::IndexMap<String, Value>;
 
-#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+/// Remove an entry from a [ValueMap]. The order of the remaining entries is preserved
+/// when the `preserve_order` feature is on.
+pub(crate) fn remove_entry(map: &mut ValueMap, key: &str) -> Option<Value> {
+    #[cfg(feature = "preserve_order")]
+    return map.shift_remove(key);
+    #[cfg(not(feature = "preserve_order"))]
+    return map.remove(key);
+}
+
+/// A JSON number.
+///
+/// [Number::Int] and [Number::Float] compare equal when they represent the same value,
+/// so `Number::Int(1) == Number::Float(1.0)`. Numbers have a total order in which `NaN` is
+/// greater than every other number and equal to itself. See the [Ord] implementation.
+#[derive(Debug, Clone)]
 pub enum Number {
     Float(f64),
     Int(i64),
+    /// An integer that doesn't fit in an [i64], stored as its exact text so that no precision
+    /// is lost. Only created when parsing with
+    /// [ParseOptions::preserve_big_integers](parse::ParseOptions::preserve_big_integers),
+    /// which writes the text without a `+` sign or leading zeros.
+    Big(String),
 }
 
 /// JSON Value.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Value {
     /// Null value.
-    /// ```no_run,json
+    /// ```json
     /// null
     /// ```
     Null,
     /// A boolean value.
-    /// ```no_run,json
+    /// ```json
     /// true
     /// ```
     /// or
-    /// ```no_run,json
+    /// ```json
     /// false
     /// ```
     Boolean(bool),
     /// An [f64] or [i64] number.
-    /// ```no_run,json
+    /// ```json
     /// 3.14159265358979
     /// ```
     Number(Number),
     /// A UTF-8 encoded string.
-    /// ```no_run,json
+    /// ```json
     /// "The quick brown fox jumps over the lazy dog.\nhello, world"
     /// ```
     /// The following characters must be escaped:  
@@ -57,7 +91,7 @@ pub enum Value {
     /// * `\u{c}`
     String(String),
     /// An array of JSON [Value]s.
-    /// ```no_run,json
+    /// ```json
     /// [
     ///     "hello, world",
     ///     1234,
@@ -69,7 +103,7 @@ pub enum Value {
     /// ```
     Array(Vec<Value>),
     /// A Mapping of JSON [Value]s by their name.
-    /// ```no_run,json
+    /// ```json
     /// {
     ///     "tag": null,
     ///     "registered": true,
@@ -140,6 +174,34 @@ impl From<i64> for Value {
     }
 }
 
+/// Implements [PartialEq] in both directions between [Value] and another type.
+macro_rules! impl_partial_eq {
+    ($($ty:ty => |$value:ident, $other:ident| $eq:expr;)*) => {$(
+        impl PartialEq<$ty> for Value {
+            fn eq(&self, other: &$ty) -> bool {
+                let ($value, $other) = (self, other);
+                $eq
+            }
+        }
+
+        impl PartialEq<Value> for $ty {
+            fn eq(&self, other: &Value) -> bool {
+                other == self
+            }
+        }
+    )*};
+}
+
+// Each comparison is `false` if the [Value] is a different variant. Numbers compare with the
+// same rules as [Number], so `Value::Number(Number::Float(1.0)) == 1` is `true`.
+impl_partial_eq! {
+    str => |value, other| matches!(value, Value::String(string) if string == other);
+    &str => |value, other| matches!(value, Value::String(string) if string == other);
+    bool => |value, other| matches!(value, Value::Boolean(boolean) if boolean == other);
+    i64 => |value, other| matches!(value, Value::Number(number) if *number == Number::Int(*other));
+    f64 => |value, other| matches!(value, Value::Number(number) if *number == Number::Float(*other));
+}
+
 /// Allows for indexing into a [Value] by [String] or [usize]
 pub trait IndexOrKey {
     /// Get an immutable reference to a [Value].
@@ -237,6 +299,17 @@ impl IndexOrKey for String {
     }
 }
 
+/// Whether a key is present in a [Value::Object]. See [Value::presence].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Presence<'a> {
+    /// The key does not exist (or the [Value] is not a [Value::Object]).
+    Absent,
+    /// The key exists and its value is [Value::Null].
+    Null,
+    /// The key exists and its value is not [Value::Null].
+    Value(&'a Value),
+}
+
 // By implementing InsertKey for String and &str, I can make Value::insert(k, v) generic for the key type.
 pub trait InsertKey {
     fn insert_into(self, map: &mut ValueMap, value: Value) -> Option<Value>;
@@ -249,12 +322,144 @@ impl InsertKey for String {
 }
 
 impl InsertKey for &str {
+    /// Only allocates an owned key when `self` is not already in `map`.
     fn insert_into(self, map: &mut ValueMap, value: Value) -> Option<Value> {
+        if let Some(existing) = map.get_mut(self) {
+            return Some(std::mem::replace(existing, value));
+        }
         map.insert(self.to_owned(), value)
     }
 }
 
 impl Value {
+    /// The name of the JSON type of this [Value]: `"null"`, `"boolean"`, `"number"`,
+    /// `"string"`, `"array"`, or `"object"`.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Null => "null",
+            Value::Boolean(_) => "boolean",
+            Value::Number(_) => "number",
+            Value::String(_) => "string",
+            Value::Array(_) => "array",
+            Value::Object(_) => "object",
+        }
+    }
+
+    /// Returns `Some(())` if this is [Value::Null]. This lets an explicit `null` be told
+    /// apart from a type mismatch in [Option] chains built from the other `as_` accessors.
+    pub fn as_null(&self) -> Option<()> {
+        matches!(self, Value::Null).then_some(())
+    }
+
+    /// Get a reference to the inner [Vec] if this is a [Value::Array].
+    pub fn as_array(&self) -> Option<&Vec<Value>> {
+        let Value::Array(array) = self else {
+            return None;
+        };
+        Some(array)
+    }
+
+    /// Borrow the elements of a [Value::Array] as a slice, or an empty slice if the [Value]
+    /// is not a [Value::Array], for read paths that don't need to tell the two apart.
+    pub fn array_slice(&self) -> &[Value] {
+        match self {
+            Value::Array(array) => array,
+            _ => &[],
+        }
+    }
+
+    /// Iterate the entries of a [Value::Object], or nothing for any other [Value].
+    /// See [Value::array_slice] for arrays.
+    pub fn object_entries_or_empty(&self) -> impl Iterator<Item = (&String, &Value)> {
+        self.as_object().into_iter().flat_map(|object| object.iter())
+    }
+
+    /// Get a reference to the inner [ValueMap] if this is a [Value::Object].
+    pub fn as_object(&self) -> Option<&ValueMap> {
+        let Value::Object(object) = self else {
+            return None;
+        };
+        Some(object)
+    }
+
+    /// Get the number as an [f64] if this is a [Value::Number]. See [Number::as_f64].
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Number(number) => Some(number.as_f64()),
+            _ => None,
+        }
+    }
+
+    /// Get the number as an [f64] if this is a [Value::Number], otherwise return `default`.
+    /// Indexing a missing key gives [Value::Null], so `value["missing"].number_or(1.0)` is `1.0`.
+    pub fn number_or(&self, default: f64) -> f64 {
+        self.as_f64().unwrap_or(default)
+    }
+
+    /// Collect the elements of a [Value::Array] of numbers into a [Vec] of [f64]s, with
+    /// integers converted by [Number::as_f64]. Returns `None` if this [Value] is not a
+    /// [Value::Array] or any element is not a [Value::Number].
+    pub fn as_f64_vec(&self) -> Option<Vec<f64>> {
+        self.as_array()?.iter().map(Value::as_f64).collect()
+    }
+
+    /// Collect the elements of a [Value::Array] of strings into a [Vec] of `&str`s.
+    /// Returns `None` if this [Value] is not a [Value::Array] or any element is not a
+    /// [Value::String].
+    pub fn as_str_vec(&self) -> Option<Vec<&str>> {
+        self.as_array()?.iter().map(|value| match value {
+            Value::String(string) => Some(string.as_str()),
+            _ => None,
+        }).collect()
+    }
+
+    /// Get a reference to the inner [Vec] if this is a [Value::Array], otherwise
+    /// return a [ConversionError] describing the mismatch.
+    pub fn try_as_array(&self) -> Result<&Vec<Value>, ConversionError> {
+        self.as_array().ok_or(ConversionError {
+            expected: "array",
+            found: self.type_name(),
+        })
+    }
+
+    /// Get a reference to the inner [ValueMap] if this is a [Value::Object], otherwise
+    /// return a [ConversionError] describing the mismatch.
+    pub fn try_as_object(&self) -> Result<&ValueMap, ConversionError> {
+        self.as_object().ok_or(ConversionError {
+            expected: "object",
+            found: self.type_name(),
+        })
+    }
+
+    /// Normalize a "single item or list" field into a [Value::Array], and return the array.
+    /// [Value::Null] becomes an empty array, arrays are left as they are, and any other
+    /// [Value] is wrapped into a single-element array.
+    pub fn ensure_array(&mut self) -> &mut Vec<Value> {
+        if !matches!(self, Value::Array(_)) {
+            *self = match std::mem::replace(self, Value::Null) {
+                Value::Null => Value::Array(Vec::new()),
+                value => Value::Array(vec![value]),
+            };
+        }
+        let Value::Array(array) = self else {
+            unreachable!();
+        };
+        array
+    }
+
+    /// Turn [Value::Null] into an empty [Value::Object], and return the object. Returns `None`
+    /// without changing anything if the [Value] is neither [Value::Null] nor [Value::Object],
+    /// because there is no key to put it under.
+    pub fn ensure_object(&mut self) -> Option<&mut ValueMap> {
+        if let Value::Null = self {
+            *self = Value::Object(ValueMap::new());
+        }
+        match self {
+            Value::Object(object) => Some(object),
+            _ => None,
+        }
+    }
+
     /// Push `value` into a [Value::Array]. If the [Value] is [Value::Null], convert it
     /// into a [Value::Array] and push `value` into it.
     /// 
@@ -293,10 +498,88 @@ impl Value {
         i_k.get_mut(self)
     }
 
+    /// Get an immutable reference to the [Value] of the first key in `keys` that exists in a
+    /// [Value::Object], which is useful for settings with multiple aliases.
+    /// Returns `None` if none of the keys exist or the [Value] is not a [Value::Object].
+    pub fn get_first(&self, keys: &[&str]) -> Option<&Value> {
+        let object = self.as_object()?;
+        keys.iter().find_map(|&key| object.get(key))
+    }
+
+    /// Check the [Presence] of `key` in a [Value::Object], distinguishing between a key
+    /// that is absent and a key that is present but [Value::Null].
+    pub fn presence(&self, key: &str) -> Presence<'_> {
+        match self.get(key) {
+            None => Presence::Absent,
+            Some(Value::Null) => Presence::Null,
+            Some(value) => Presence::Value(value),
+        }
+    }
+
+    /// Get a mutable reference to the child at `segment`, creating it if it doesn't exist.
+    /// [Value::Null] is converted into a [Value::Array] if `segment` is numeric, otherwise into a
+    /// [Value::Object]. Arrays are extended with [Value::Null] up to the index.
+    ///
+    /// Panics if self [Value] is not [Value::Null], [Value::Array], or [Value::Object], or if
+    /// it is a [Value::Array] and `segment` is not numeric.
+    fn path_entry(&mut self, segment: &str) -> &mut Value {
+        if let Value::Null = self {
+            *self = match segment.parse::<usize>() {
+                Ok(_) => Value::Array(Vec::new()),
+                Err(_) => Value::Object(ValueMap::new()),
+            };
+        }
+        match self {
+            Value::Object(object) => object.entry(segment.to_owned()).or_insert(Value::Null),
+            Value::Array(array) => {
+                let Ok(index) = segment.parse::<usize>() else {
+                    panic!("Not an object.");
+                };
+                if index >= array.len() {
+                    array.resize(index + 1, Value::Null);
+                }
+                &mut array[index]
+            }
+            _ => panic!("Not an array or object."),
+        }
+    }
+
+    /// Insert `value` at a dot-separated `path` (e.g. `"users.3.name"`), creating
+    /// intermediate [Value::Object]s and [Value::Array]s as needed. A numeric segment
+    /// past the end of an array extends the array with [Value::Null] up to that index.
+    /// Returns the previous value if one existed at `path`.
+    ///
+    /// Panics if a [Value] along the path is not [Value::Null], [Value::Array], or [Value::Object].
+    pub fn insert_path(&mut self, path: &str, value: Value) -> Option<Value> {
+        let (parent, last) = match path.rsplit_once('.') {
+            Some((parent, last)) => (parent.split('.').fold(self, Value::path_entry), last),
+            None => (self, path),
+        };
+        let existed = match parent {
+            Value::Object(object) => object.contains_key(last),
+            Value::Array(array) => last.parse::<usize>().is_ok_and(|index| index < array.len()),
+            _ => false,
+        };
+        let previous = std::mem::replace(parent.path_entry(last), value);
+        existed.then_some(previous)
+    }
+
+    /// Set the [Value] at a dot-separated `path` (e.g. `"a.b.c"`), creating intermediate
+    /// containers as needed. This is [Value::insert_path] accepting anything that converts
+    /// into a [Value], for builder-style code.
+    ///
+    /// Panics if a [Value] along the path is not [Value::Null], [Value::Array], or [Value::Object].
+    pub fn set<T: Into<Value>>(&mut self, path: &str, value: T) -> Option<Value> {
+        self.insert_path(path, value.into())
+    }
+
     /// Get the length of the [Value] if it is one of the following variants:
     /// * [Value::String]
     /// * [Value::Array]
     /// * [Value::Object]
+    ///
+    /// The length of a string is in bytes of UTF-8, like [str::len], so `"é"` has a length
+    /// of 2. Use [Value::char_len] to count characters instead.
     pub fn len(&self) -> usize {
         match self {
             Value::String(string) => string.len(),
@@ -305,6 +588,70 @@ impl Value {
             _ => 0,
         }
     }
+
+    /// Get the length of the [Value] like [Value::len], except that the length of a
+    /// [Value::String] is its number of characters (Unicode scalar values) rather than bytes.
+    /// This is still not the number of user-perceived characters: `"e\u{301}"` has a
+    /// `char_len` of 2.
+    pub fn char_len(&self) -> usize {
+        match self {
+            Value::String(string) => string.chars().count(),
+            other => other.len(),
+        }
+    }
+
+    /// Checks if the [Value] has a length of zero. See [Value::len].
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Checks if the [Value] is "truthy", similar to JavaScript. The following values are falsy,
+    /// and everything else is truthy:
+    /// * [Value::Null]
+    /// * `false`
+    /// * `0`, `0.0`, `-0.0`, and `NaN`
+    /// * `""` (the empty string)
+    /// * `[]` (the empty array). Unlike JavaScript.
+    /// * `{}` (the empty object). Unlike JavaScript.
+    pub fn is_truthy(&self) -> bool {
+        match self {
+            Value::Null => false,
+            &Value::Boolean(boolean) => boolean,
+            &Value::Number(Number::Int(int)) => int != 0,
+            &Value::Number(Number::Float(float)) => float != 0.0 && !float.is_nan(),
+            Value::Number(Number::Big(_)) => true,
+            Value::String(_) | Value::Array(_) | Value::Object(_) => !self.is_empty(),
+        }
+    }
+
+    /// Sort a [Value::Array] with a comparator function. Does nothing if the [Value] is not a [Value::Array].
+    pub fn sort_array_by<F: FnMut(&Value, &Value) -> std::cmp::Ordering>(&mut self, compare: F) {
+        if let Value::Array(array) = self {
+            array.sort_by(compare);
+        }
+    }
+
+    /// Split a [Value::Array] at `at`, leaving the elements before `at` in place and returning
+    /// the rest as a new [Value::Array], such as to take batches. `at` is clamped to the
+    /// length of the array. Returns [Value::Null] without changing anything if the [Value]
+    /// is not a [Value::Array].
+    pub fn split_off_array(&mut self, at: usize) -> Value {
+        match self {
+            Value::Array(array) => Value::Array(array.split_off(at.min(array.len()))),
+            _ => Value::Null,
+        }
+    }
+
+    /// Keep only the elements of a [Value::Array] in the range `start..end`, such as one page
+    /// of results. Both bounds are clamped to the length of the array, and the array is left
+    /// empty if `start >= end`. Does nothing if the [Value] is not a [Value::Array].
+    pub fn slice_array(&mut self, start: usize, end: usize) {
+        if let Value::Array(array) = self {
+            let end = end.min(array.len());
+            array.truncate(end);
+            array.drain(..start.min(end));
+        }
+    }
 }
 
 impl<I: IndexOrKey> std::ops::Index<I> for Value {
@@ -347,6 +694,7 @@ mod tests {
 
     use super::*;
     #[test]
+    #[allow(clippy::approx_constant)]
     fn parse_number_test() -> Result<(), crate::error::ParseError> {
         let object = Value::from_str(r#"
             {
@@ -360,4 +708,272 @@ mod tests {
         assert_eq!(json_text, r#"{"int":9223372036854775807,"float":3.14159265358979}"#);
         Ok(())
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn partial_eq_test() -> Result<(), crate::error::ParseError> {
+        let value = Value::from_str(r#"["hello", true, 42, 1.5, null]"#)?;
+        assert!(value[0] == "hello");
+        assert!("hello" == value[0]);
+        assert!(value[0] == *"hello");
+        assert!(value[0] != "world");
+        assert!(value[1] == true);
+        assert!(false != value[1]);
+        assert!(value[2] == 42);
+        assert!(value[2] == 42.0);
+        assert!(42 == value[2]);
+        assert!(value[3] == 1.5);
+        assert!(1.5 == value[3]);
+        assert!(value[3] != 1);
+        // Mismatched types are never equal.
+        assert!(value[0] != true);
+        assert!(value[1] != "true");
+        assert!(value[2] != "42");
+        assert!(value[4] != 0);
+        assert!(value[4] != false);
+        assert!(value[4] != "null");
+        Ok(())
+    }
+
+    #[test]
+    fn insert_path_test() {
+        let mut value = Value::Null;
+        assert!(value.insert_path("3", Value::from("three")).is_none());
+        assert!(matches!(&value, Value::Array(array) if array.len() == 4));
+        for i in 0..3 {
+            assert!(matches!(value[i], Value::Null));
+        }
+        assert!(matches!(&value[3], Value::String(s) if s == "three"));
+
+        let mut value = Value::Null;
+        value.insert_path("users.1.name", Value::from("Fred"));
+        assert!(matches!(value["users"][0], Value::Null));
+        assert!(matches!(&value["users"][1]["name"], Value::String(s) if s == "Fred"));
+        let previous = value.insert_path("users.1.name", Value::from("George"));
+        assert!(matches!(previous, Some(Value::String(s)) if s == "Fred"));
+        assert!(matches!(&value["users"][1]["name"], Value::String(s) if s == "George"));
+    }
+
+    #[test]
+    fn set_test() -> Result<(), crate::error::ParseError> {
+        let mut value = Value::Null;
+        assert!(value.set("a.b.c", 1i64).is_none());
+        value.set("a.list.1", "second");
+        assert!(value.set("a.b.c", true) == Some(Value::from(1i64)));
+        assert_eq!(value, Value::from_str(r#"{"a": {"b": {"c": true}, "list": [null, "second"]}}"#)?);
+        Ok(())
+    }
+
+    #[test]
+    fn split_off_array_test() -> Result<(), crate::error::ParseError> {
+        let mut value = Value::from_str("[0, 1, 2, 3, 4, 5, 6, 7, 8, 9]")?;
+        let rest = value.split_off_array(4);
+        assert_eq!(value, Value::from_str("[0, 1, 2, 3]")?);
+        assert_eq!(rest, Value::from_str("[4, 5, 6, 7, 8, 9]")?);
+        assert_eq!(value.split_off_array(10), Value::from_str("[]")?);
+        assert_eq!(value.len(), 4);
+        let mut text = Value::from("text");
+        assert!(matches!(text.split_off_array(0), Value::Null));
+        assert!(text == "text");
+        Ok(())
+    }
+
+    #[test]
+    fn slice_array_test() -> Result<(), crate::error::ParseError> {
+        let numbers = Value::from_str("[0, 1, 2, 3, 4, 5]")?;
+        let sliced = |start, end| {
+            let mut value = numbers.clone();
+            value.slice_array(start, end);
+            value
+        };
+        assert_eq!(sliced(2, 4), Value::from_str("[2, 3]")?);
+        assert_eq!(sliced(0, 6), numbers);
+        assert_eq!(sliced(4, 100), Value::from_str("[4, 5]")?);
+        assert_eq!(sliced(10, 20), Value::from_str("[]")?);
+        assert_eq!(sliced(4, 2), Value::from_str("[]")?);
+        let mut object = Value::from_str(r#"{"a": 1}"#)?;
+        object.slice_array(0, 0);
+        assert_eq!(object, Value::from_str(r#"{"a": 1}"#)?);
+        Ok(())
+    }
+
+    #[test]
+    fn sort_array_by_test() {
+        let mut value = Value::Array(["Charlie", "Alice", "Bob"].into_iter().map(|name| {
+            let mut record = Value::Null;
+            record.insert("name", name);
+            record
+        }).collect());
+        value.sort_array_by(|a, b| {
+            match (&a["name"], &b["name"]) {
+                (Value::String(a), Value::String(b)) => a.cmp(b),
+                _ => std::cmp::Ordering::Equal,
+            }
+        });
+        let names: Vec<String> = (0..3).map(|i| value[i]["name"].to_string()).collect();
+        assert_eq!(names, [r#""Alice""#, r#""Bob""#, r#""Charlie""#]);
+
+        let mut value = Value::from("not an array");
+        value.sort_array_by(|_, _| std::cmp::Ordering::Less);
+        assert!(matches!(&value, Value::String(s) if s == "not an array"));
+    }
+
+    #[test]
+    fn array_slice_test() -> Result<(), crate::error::ParseError> {
+        let value = Value::from_str(r#"{"list": [1, 2, 3], "empty": [], "text": "abc"}"#)?;
+        assert!(value["list"].array_slice().iter().eq([1, 2, 3].iter()));
+        assert!(value["empty"].array_slice().is_empty());
+        assert!(value["text"].array_slice().is_empty());
+        assert!(value["missing"].array_slice().is_empty());
+        assert!(value.array_slice().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn or_empty_test() -> Result<(), crate::error::ParseError> {
+        let value = Value::from_str(r#"{"a": [1, 2], "b": "text"}"#)?;
+        assert_eq!(value.object_entries_or_empty().count(), 2);
+        assert!(value.object_entries_or_empty().any(|(key, value)| key == "b" && *value == "text"));
+        assert_eq!(value["a"].array_slice().len(), 2);
+        for other in [&value["a"], &value["b"], &value["missing"], &Value::from(1i64), &Value::from(true)] {
+            assert_eq!(other.object_entries_or_empty().count(), 0);
+        }
+        for other in [&value, &value["b"], &value["missing"], &Value::from(1i64), &Value::from(true)] {
+            assert!(other.array_slice().is_empty());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_test() -> Result<(), crate::error::ParseError> {
+        let mut value = Value::from_str(r#"{"one": "a", "many": ["a", "b"], "none": null}"#)?;
+        value["one"].ensure_array();
+        value["many"].ensure_array().push(Value::from("c"));
+        assert!(value["none"].ensure_array().is_empty());
+        assert_eq!(value, Value::from_str(r#"{"one": ["a"], "many": ["a", "b", "c"], "none": []}"#)?);
+
+        let mut value = Value::from_str(r#"{"object": {"a": 1}, "none": null, "text": "a"}"#)?;
+        assert_eq!(value["object"].ensure_object().map(|object| object.len()), Some(1));
+        assert!(value["none"].ensure_object().is_some_and(|object| object.is_empty()));
+        assert!(value["text"].ensure_object().is_none());
+        assert_eq!(value, Value::from_str(r#"{"object": {"a": 1}, "none": {}, "text": "a"}"#)?);
+        Ok(())
+    }
+
+    #[test]
+    fn try_as_test() {
+        let mut value = Value::Null;
+        value.insert("list", vec![Value::from(1i64), Value::from(2i64)]);
+        assert_eq!(value.try_as_object().map(ValueMap::len).ok(), Some(1));
+        assert_eq!(value["list"].try_as_array().map(Vec::len).ok(), Some(2));
+
+        let error = value.try_as_array().unwrap_err();
+        assert_eq!(error.expected, "array");
+        assert_eq!(error.found, "object");
+        let error = value["list"][0].try_as_object().unwrap_err();
+        assert_eq!(error.found, "number");
+        assert_eq!(error.to_string(), "Expected object, found number.");
+    }
+
+    #[test]
+    fn get_first_test() {
+        let mut value = Value::Null;
+        value.insert("color", "red");
+        value.insert("colour", "blue");
+        assert!(matches!(value.get_first(&["colour", "color"]), Some(Value::String(s)) if s == "blue"));
+        assert!(matches!(value.get_first(&["hue", "color"]), Some(Value::String(s)) if s == "red"));
+        assert!(value.get_first(&["hue", "shade"]).is_none());
+        assert!(Value::from("color").get_first(&["color"]).is_none());
+    }
+
+    #[test]
+    fn presence_test() {
+        let mut value = Value::Null;
+        value.insert("null", Value::Null);
+        value.insert("name", "Fred");
+        assert_eq!(value.presence("missing"), Presence::Absent);
+        assert_eq!(value.presence("null"), Presence::Null);
+        assert_eq!(value.presence("name"), Presence::Value(&Value::from("Fred")));
+        assert_eq!(Value::Null.presence("null"), Presence::Absent);
+    }
+
+    #[test]
+    fn as_null_test() -> Result<(), crate::error::ParseError> {
+        let value = Value::from_str(r#"{"nothing": null, "text": "null"}"#)?;
+        assert_eq!(value["nothing"].as_null(), Some(()));
+        assert_eq!(value["text"].as_null(), None);
+        assert_eq!(Value::from_str("[]")?.as_null(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn is_truthy_test() {
+        let falsy = [
+            Value::Null,
+            Value::Boolean(false),
+            Value::from(0i64),
+            Value::from(0.0),
+            Value::from(-0.0),
+            Value::from(f64::NAN),
+            Value::from(""),
+            Value::Array(Vec::new()),
+            Value::Object(ValueMap::new()),
+        ];
+        for value in falsy {
+            assert!(!value.is_truthy(), "{value:?}");
+        }
+        let mut object = Value::Null;
+        object.insert("a", Value::Null);
+        let truthy = [
+            Value::Boolean(true),
+            Value::from(-1i64),
+            Value::from(0.5),
+            Value::from("false"),
+            Value::from(vec![Value::Null]),
+            object,
+        ];
+        for value in truthy {
+            assert!(value.is_truthy(), "{value:?}");
+        }
+    }
+
+    #[test]
+    fn number_or_test() {
+        let mut value = Value::Null;
+        value.insert("int", 3i64);
+        value.insert("float", 2.5);
+        value.insert("string", "4");
+        value.insert("null", Value::Null);
+        assert_eq!(value["int"].number_or(1.0), 3.0);
+        assert_eq!(value["float"].number_or(1.0), 2.5);
+        assert_eq!(value["string"].number_or(1.0), 1.0);
+        assert_eq!(value["null"].number_or(1.0), 1.0);
+        assert_eq!(value["missing"].number_or(1.0), 1.0);
+    }
+
+    #[test]
+    fn typed_vec_test() -> Result<(), crate::error::ParseError> {
+        let value = Value::from_str(r#"{"numbers": [1, 2.5, -3], "mixed": [1, "2"], "strings": ["a", "b"], "empty": []}"#)?;
+        assert_eq!(value["numbers"].as_f64_vec(), Some(vec![1.0, 2.5, -3.0]));
+        assert_eq!(value["mixed"].as_f64_vec(), None);
+        assert_eq!(value["strings"].as_f64_vec(), None);
+        assert_eq!(value["empty"].as_f64_vec(), Some(Vec::new()));
+        assert_eq!(value["strings"].as_str_vec(), Some(vec!["a", "b"]));
+        assert_eq!(value["mixed"].as_str_vec(), None);
+        assert_eq!(value["numbers"].as_str_vec(), None);
+        assert_eq!(value.as_f64_vec(), None);
+        assert_eq!(value["missing"].as_str_vec(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn char_len_test() -> Result<(), crate::error::ParseError> {
+        let value = Value::from("héllo → 世界");
+        assert_eq!(value.len(), 17);
+        assert_eq!(value.char_len(), 10);
+        assert_eq!(Value::from("ascii").char_len(), 5);
+        assert_eq!(Value::from_str(r#"["é", "ü"]"#)?.char_len(), 2);
+        assert_eq!(Value::from_str(r#"{"é": 1}"#)?.char_len(), 1);
+        assert_eq!(Value::Null.char_len(), 0);
+        Ok(())
+    }
+}
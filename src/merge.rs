@@ -0,0 +1,254 @@
+use crate::{remove_entry, Value, ValueMap};
+
+impl Value {
+    /// Create a JSON Merge Patch (RFC 7386) that transforms this [Value] into `target`
+    /// when applied with [Value::merge_patch].
+    ///
+    /// The patch is minimal: keys that are equal on both sides are omitted entirely, keys
+    /// that were removed are set to [Value::Null], and nested objects produce nested patches.
+    /// Because [Value::Null] means removal in a merge patch, `null` values in `target` objects
+    /// cannot be represented.
+    pub fn diff(&self, target: &Value) -> Value {
+        let (Value::Object(source), Value::Object(target)) = (self, target) else {
+            return target.clone();
+        };
+        let mut patch = ValueMap::new();
+        for key in source.keys() {
+            if !target.contains_key(key) {
+                patch.insert(key.clone(), Value::Null);
+            }
+        }
+        for (key, value) in target {
+            match source.get(key) {
+                Some(old) if old == value => (),
+                Some(old) => { patch.insert(key.clone(), old.diff(value)); }
+                None => { patch.insert(key.clone(), value.clone()); }
+            }
+        }
+        Value::Object(patch)
+    }
+
+    /// Checks if `superset` contains everything in this [Value], such as to check a response
+    /// against an expected template.
+    ///
+    /// Every key of an object must be in the `superset` object with a value that this value
+    /// is a subset of, but `superset` may have extra keys. Arrays must have the same length,
+    /// and each element must be a subset of the element at the same index. Any other values
+    /// must be equal.
+    pub fn is_subset_of(&self, superset: &Value) -> bool {
+        match (self, superset) {
+            (Value::Object(object), Value::Object(superset)) => object.iter().all(|(key, value)| {
+                superset.get(key).is_some_and(|other| value.is_subset_of(other))
+            }),
+            (Value::Array(array), Value::Array(superset)) => {
+                array.len() == superset.len()
+                && array.iter().zip(superset).all(|(value, other)| value.is_subset_of(other))
+            }
+            (value, other) => value == other,
+        }
+    }
+
+    /// Apply a JSON Merge Patch (RFC 7386) to this [Value].
+    ///
+    /// If `patch` is an object, its entries are merged recursively and entries that are
+    /// [Value::Null] remove the key. Otherwise this [Value] is replaced with `patch`.
+    pub fn merge_patch(&mut self, patch: &Value) {
+        let Value::Object(patch) = patch else {
+            *self = patch.clone();
+            return;
+        };
+        if !matches!(self, Value::Object(_)) {
+            *self = Value::Object(ValueMap::new());
+        }
+        let Value::Object(object) = self else {
+            unreachable!();
+        };
+        for (key, value) in patch {
+            if let Value::Null = value {
+                remove_entry(object, key);
+            } else {
+                object.entry(key.clone()).or_insert(Value::Null).merge_patch(value);
+            }
+        }
+    }
+
+    /// Deep merge `other` into this [Value], calling `resolve` to settle conflicts.
+    ///
+    /// When both values are objects, keys that are only in `other` are moved in and keys
+    /// that are in both are merged recursively. Otherwise `resolve` is called with this
+    /// [Value] and the conflicting [Value] from `other`, and decides what to keep, so a
+    /// resolver of `|old, new| *old = new` gives "other wins". Unlike [Value::merge_patch],
+    /// [Value::Null] has no special meaning.
+    pub fn merge_with_resolver<F: FnMut(&mut Value, Value)>(&mut self, other: Value, mut resolve: F) {
+        fn merge_impl<F: FnMut(&mut Value, Value)>(value: &mut Value, other: Value, resolve: &mut F) {
+            match (value, other) {
+                (Value::Object(object), Value::Object(other)) => {
+                    for (key, other) in other {
+                        match object.get_mut(&key) {
+                            Some(existing) => merge_impl(existing, other, resolve),
+                            None => { object.insert(key, other); }
+                        }
+                    }
+                }
+                (value, other) => resolve(value, other),
+            }
+        }
+        merge_impl(self, other, &mut resolve);
+    }
+
+    /// Deep merge `docs` from left to right, so that each document overrides the ones before
+    /// it, such as layers of configuration (defaults, profile, environment, overrides).
+    /// Objects are merged recursively and any other value replaces the previous one (see
+    /// [Value::merge_with_resolver]). Returns [Value::Null] if `docs` is empty.
+    pub fn merge_all<I: IntoIterator<Item = Value>>(docs: I) -> Value {
+        docs.into_iter().fold(Value::Null, |mut merged, doc| {
+            merged.merge_with_resolver(doc, |old, new| *old = new);
+            merged
+        })
+    }
+
+    /// Deep merge `patch` into the [Value] at a JSON Pointer, creating it and its ancestors
+    /// if they don't exist (see [Value::pointer_or_insert]). Objects are merged recursively
+    /// and any other value in `patch` replaces the existing one, like [Value::merge_all], so
+    /// unlike [Value::merge_patch], [Value::Null] is kept rather than deleting the key.
+    ///
+    /// Returns `false` without changing anything if the location can't be reached.
+    pub fn merge_at(&mut self, pointer: &str, patch: Value) -> bool {
+        match self.pointer_or_insert(pointer) {
+            Some(value) => {
+                value.merge_with_resolver(patch, |old, new| *old = new);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::error::ParseError;
+
+    /// Checks that no key in `patch` maps to a value that is the same in `source` and `target`.
+    fn assert_minimal(patch: &Value, source: &Value, target: &Value) {
+        let Value::Object(patch) = patch else {
+            return;
+        };
+        for (key, value) in patch {
+            assert!(source.get(key.as_str()) != target.get(key.as_str()), "unchanged key {key} in patch");
+            assert_minimal(value, &source[key.as_str()], &target[key.as_str()]);
+        }
+    }
+
+    #[test]
+    fn diff_test() -> Result<(), ParseError> {
+        let source = Value::from_str(r#"
+            {
+                "title": "Hello",
+                "author": {"givenName": "John", "familyName": "Doe"},
+                "tags": ["example", "sample"],
+                "content": "This will be unchanged",
+                "draft": true
+            }
+        "#)?;
+        let target = Value::from_str(r#"
+            {
+                "title": "Hello!",
+                "author": {"givenName": "John"},
+                "tags": ["example"],
+                "content": "This will be unchanged",
+                "phoneNumber": "+01-123-456-7890"
+            }
+        "#)?;
+        let patch = source.diff(&target);
+        let expected = Value::from_str(r#"
+            {
+                "title": "Hello!",
+                "author": {"familyName": null},
+                "tags": ["example"],
+                "draft": null,
+                "phoneNumber": "+01-123-456-7890"
+            }
+        "#)?;
+        assert_eq!(patch, expected);
+        assert!(patch.get("content").is_none());
+        assert!(patch["author"].get("givenName").is_none());
+        assert_minimal(&patch, &source, &target);
+
+        let mut patched = source.clone();
+        patched.merge_patch(&patch);
+        assert_eq!(patched, target);
+
+        assert_eq!(source.diff(&source), Value::Object(ValueMap::new()));
+        Ok(())
+    }
+
+    #[test]
+    fn merge_at_test() -> Result<(), ParseError> {
+        let mut value = Value::from_str(r#"{"a": {"x": 1}, "n": 5}"#)?;
+        assert!(value.merge_at("/a/b", Value::from_str(r#"{"count": 1, "tags": ["new"]}"#)?));
+        assert!(value.merge_at("/a/b", Value::from_str(r#"{"total": 2}"#)?));
+        let expected = Value::from_str(r#"{"a": {"x": 1, "b": {"count": 1, "tags": ["new"], "total": 2}}, "n": 5}"#)?;
+        assert_eq!(value, expected);
+        assert!(!value.merge_at("/n/b", Value::from_str(r#"{"total": 2}"#)?));
+        assert_eq!(value, expected);
+
+        // Null replaces a value instead of deleting its key.
+        assert!(value.merge_at("/a", Value::from_str(r#"{"x": null, "b": {"tags": null, "new": null}}"#)?));
+        let expected = Value::from_str(r#"{"a": {"x": null, "b": {"count": 1, "tags": null, "total": 2, "new": null}}, "n": 5}"#)?;
+        assert_eq!(value, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn merge_with_resolver_test() -> Result<(), ParseError> {
+        let mut value = Value::from_str(r#"{"hits": 3, "pages": {"home": 10, "about": 1}, "name": "site"}"#)?;
+        let other = Value::from_str(r#"{"hits": 4, "pages": {"home": 5, "blog": 2}, "name": "other", "new": null}"#)?;
+        value.merge_with_resolver(other, |existing, incoming| {
+            if let (Some(lhs), Some(rhs)) = (existing.as_f64(), incoming.as_f64()) {
+                *existing = Value::from(lhs + rhs);
+            }
+        });
+        let expected = Value::from_str(r#"{"hits": 7, "pages": {"home": 15, "about": 1, "blog": 2}, "name": "site", "new": null}"#)?;
+        assert_eq!(value, expected);
+
+        let mut value = Value::from_str(r#"{"a": 1}"#)?;
+        value.merge_with_resolver(Value::from_str("[1]")?, |old, new| *old = new);
+        assert_eq!(value, Value::from_str("[1]")?);
+        Ok(())
+    }
+
+    #[test]
+    fn merge_all_test() -> Result<(), ParseError> {
+        let defaults = Value::from_str(r#"{"log": {"level": "info", "file": null}, "port": 80, "hosts": ["a", "b"]}"#)?;
+        let profile = Value::from_str(r#"{"log": {"level": "debug"}, "port": 8080}"#)?;
+        let overrides = Value::from_str(r#"{"log": {"file": "out.log"}, "port": 9000, "hosts": ["c"]}"#)?;
+        let merged = Value::merge_all([defaults, profile, overrides]);
+        let expected = Value::from_str(r#"{"log": {"level": "debug", "file": "out.log"}, "port": 9000, "hosts": ["c"]}"#)?;
+        assert_eq!(merged, expected);
+        assert!(matches!(Value::merge_all([]), Value::Null));
+        Ok(())
+    }
+
+    #[test]
+    fn is_subset_of_test() -> Result<(), ParseError> {
+        let document = Value::from_str(r#"
+            {"id": 7, "name": "Fred", "tags": [{"k": "a", "v": 1}, {"k": "b", "v": 2}], "meta": {"x": null}}
+        "#)?;
+        let template = Value::from_str(r#"{"name": "Fred", "tags": [{"k": "a"}, {}], "meta": {}}"#)?;
+        assert!(template.is_subset_of(&document));
+        assert!(document.is_subset_of(&document));
+        assert!(!document.is_subset_of(&template));
+
+        let differs = Value::from_str(r#"{"name": "Fred", "tags": [{"k": "a"}, {"k": "c"}]}"#)?;
+        assert!(!differs.is_subset_of(&document));
+        let shorter = Value::from_str(r#"{"tags": [{"k": "a"}]}"#)?;
+        assert!(!shorter.is_subset_of(&document));
+        let missing = Value::from_str(r#"{"meta": {"y": null}}"#)?;
+        assert!(!missing.is_subset_of(&document));
+        assert!(Value::from(1i64).is_subset_of(&Value::from(1.0)));
+        Ok(())
+    }
+}
@@ -0,0 +1,329 @@
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
+use std::ops::{Add, Mul, Sub};
+
+use crate::Number;
+
+/// `2^63` is the first [f64] past [i64::MAX]; `-2^63` is exactly [i64::MIN].
+const I64_BOUND: f64 = 9223372036854775808.0;
+
+/// Convert a float into an [i64] if it is integral and can be represented exactly.
+fn float_as_int(float: f64) -> Option<i64> {
+    if float.fract() == 0.0 && (-I64_BOUND..I64_BOUND).contains(&float) {
+        Some(float as i64)
+    } else {
+        None
+    }
+}
+
+/// Compare an [i64] to an [f64] without losing precision by converting the [i64].
+fn cmp_int_float(int: i64, float: f64) -> Option<Ordering> {
+    if float.is_nan() {
+        None
+    } else if float >= I64_BOUND {
+        Some(Ordering::Less)
+    } else if float < -I64_BOUND {
+        Some(Ordering::Greater)
+    } else {
+        let whole = float.trunc();
+        // The whole part is within range, so this cast is exact.
+        match int.cmp(&(whole as i64)) {
+            Ordering::Equal => whole.partial_cmp(&float),
+            ordering => Some(ordering),
+        }
+    }
+}
+
+impl Number {
+    /// Convert the number into an [f64]. [Number::Int] and [Number::Big] may lose precision.
+    pub fn as_f64(&self) -> f64 {
+        match *self {
+            Number::Float(float) => float,
+            Number::Int(int) => int as f64,
+            // Big integers always parse as floats, but may become infinite.
+            Number::Big(ref text) => text.parse().unwrap_or(f64::NAN),
+        }
+    }
+}
+
+/// Implements a checked inherent method and an operator for an arithmetic operation on [Number].
+macro_rules! arithmetic {
+    ($($checked:ident, $trait:ident, $method:ident, $op:tt;)*) => {
+        impl Number {$(
+            #[doc = concat!("Compute `self ", stringify!($op), " rhs`. Two [Number::Int]s give a [Number::Int], ")]
+            #[doc = "or `None` if the result overflows. If either operand is a [Number::Float], the result is a [Number::Float]."]
+            #[doc = "Returns `None` if either operand is a [Number::Big]."]
+            pub fn $checked(&self, rhs: &Number) -> Option<Number> {
+                match (self, rhs) {
+                    (&Number::Int(lhs), &Number::Int(rhs)) => lhs.$checked(rhs).map(Number::Int),
+                    (Number::Big(_), _) | (_, Number::Big(_)) => None,
+                    (lhs, rhs) => Some(Number::Float(lhs.as_f64() $op rhs.as_f64())),
+                }
+            }
+        )*}
+
+        $(
+            impl $trait for Number {
+                type Output = Number;
+
+                #[doc = concat!("Compute `self ", stringify!($op), " rhs`, like [Number::", stringify!($checked), "], except")]
+                #[doc = "that overflow and [Number::Big] operands give a [Number::Float] instead of `None`."]
+                fn $method(self, rhs: Number) -> Number {
+                    self.$checked(&rhs).unwrap_or_else(|| Number::Float(self.as_f64() $op rhs.as_f64()))
+                }
+            }
+        )*
+    };
+}
+
+arithmetic! {
+    checked_add, Add, add, +;
+    checked_sub, Sub, sub, -;
+    checked_mul, Mul, mul, *;
+}
+
+/// Compare two floats, treating every `NaN` as equal to each other and greater than
+/// every other float.
+fn cmp_float(lhs: f64, rhs: f64) -> Ordering {
+    lhs.partial_cmp(&rhs).unwrap_or_else(|| lhs.is_nan().cmp(&rhs.is_nan()))
+}
+
+/// Split the text of an integer into whether it's negative and its digits without leading
+/// zeros, or `None` if it's not an integer.
+fn integer_parts(text: &str) -> Option<(bool, &str)> {
+    let (negative, digits) = match text.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, text.strip_prefix('+').unwrap_or(text)),
+    };
+    if digits.is_empty() || !digits.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    let digits = digits.trim_start_matches('0');
+    // Negative zero is zero.
+    Some((negative && !digits.is_empty(), digits))
+}
+
+/// Normalize the text of an integer for a [Number::Big]: no `+` sign and no leading zeros.
+/// Text that is not an integer is kept as it is.
+pub(crate) fn normalize_big(text: &str) -> String {
+    match integer_parts(text) {
+        Some((true, digits)) => format!("-{digits}"),
+        Some((false, "")) => "0".to_owned(),
+        Some((false, digits)) => digits.to_owned(),
+        None => text.to_owned(),
+    }
+}
+
+/// Compare the text of two [Number::Big]s, numerically if they're both integers, so that
+/// `"007"` and `"7"` are equal. Integers are less than other text, which is ordered by the
+/// text itself.
+fn cmp_big(lhs: &str, rhs: &str) -> Ordering {
+    match (integer_parts(lhs), integer_parts(rhs)) {
+        (Some((lhs_negative, lhs_digits)), Some((rhs_negative, rhs_digits))) => {
+            let magnitude = lhs_digits.len().cmp(&rhs_digits.len()).then_with(|| lhs_digits.cmp(rhs_digits));
+            match (lhs_negative, rhs_negative) {
+                (true, false) => Ordering::Less,
+                (false, true) => Ordering::Greater,
+                (true, true) => magnitude.reverse(),
+                (false, false) => magnitude,
+            }
+        }
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => lhs.cmp(rhs),
+    }
+}
+
+/// Compare the text of a [Number::Big] to another number exactly. A [Number::Big] is
+/// greater than an equal [Number::Int] or [Number::Float], so that it's only equal to
+/// another [Number::Big].
+fn cmp_big_number(text: &str, other: &Number) -> Ordering {
+    let ordering = match *other {
+        Number::Big(ref other) => return cmp_big(text, other),
+        Number::Int(int) => cmp_big(text, &int.to_string()),
+        Number::Float(float) if float.is_nan() => Ordering::Less,
+        Number::Float(float) if float == f64::INFINITY => Ordering::Less,
+        Number::Float(float) if float == f64::NEG_INFINITY => Ordering::Greater,
+        Number::Float(float) => {
+            // Whole floats are written exactly, so this compares to the integer below
+            // `float`, and an integer equal to that is less than `float` unless it's whole.
+            let whole = float.floor();
+            match cmp_big(text, &format!("{whole:.0}")) {
+                Ordering::Equal if whole != float => Ordering::Less,
+                ordering => ordering,
+            }
+        }
+    };
+    ordering.then(Ordering::Greater)
+}
+
+impl PartialEq for Number {
+    /// Every `NaN` is equal to each other, so that [Number] can be [Eq].
+    /// [Number::Big] is only equal to a [Number::Big] with the same integer value.
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Number {}
+
+impl PartialOrd for Number {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Number {
+    /// Numbers are ordered by value, with `NaN` greater than every other number (and equal to
+    /// each other), so that arrays of numbers can be sorted.
+    ///
+    /// [Number::Big] is compared exactly by its integer value, and is greater than a
+    /// [Number::Int] or [Number::Float] with the same value.
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (&Number::Int(lhs), &Number::Int(rhs)) => lhs.cmp(&rhs),
+            (&Number::Float(lhs), &Number::Float(rhs)) => cmp_float(lhs, rhs),
+            // Comparing to NaN is the only case without an ordering.
+            (&Number::Int(int), &Number::Float(float)) => cmp_int_float(int, float).unwrap_or(Ordering::Less),
+            (&Number::Float(float), &Number::Int(int)) => cmp_int_float(int, float).map_or(Ordering::Greater, Ordering::reverse),
+            (Number::Big(lhs), _) => cmp_big_number(lhs, other),
+            (_, Number::Big(rhs)) => cmp_big_number(rhs, self).reverse(),
+        }
+    }
+}
+
+impl Hash for Number {
+    /// Integral floats hash the same as the equivalent [Number::Int] so that hashing agrees with
+    /// equality. Other floats are hashed by their bit pattern, with every `NaN` hashing the same.
+    /// [Number::Big] is hashed by its integer value, ignoring a `+` sign and leading zeros.
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match *self {
+            Number::Int(int) => int.hash(state),
+            Number::Big(ref text) => match integer_parts(text) {
+                Some(parts) => parts.hash(state),
+                None => text.hash(state),
+            },
+            Number::Float(float) => match float_as_int(float) {
+                Some(int) => int.hash(state),
+                None if float.is_nan() => f64::NAN.to_bits().hash(state),
+                None => float.to_bits().hash(state),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::DefaultHasher;
+
+    use super::*;
+
+    fn hash_of(number: Number) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        number.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn cross_variant_eq_test() {
+        assert_eq!(Number::Int(1), Number::Float(1.0));
+        assert_eq!(Number::Float(-0.0), Number::Int(0));
+        assert_ne!(Number::Int(1), Number::Float(1.5));
+        assert_ne!(Number::Int(i64::MAX), Number::Float(I64_BOUND));
+        assert_eq!(Number::Int(i64::MIN), Number::Float(-I64_BOUND));
+        assert!(Number::Int(1) < Number::Float(1.5));
+        assert!(Number::Float(-0.5) < Number::Int(0));
+        assert!(Number::Int(i64::MAX) < Number::Float(I64_BOUND));
+        assert!(Number::Int(i64::MAX) < Number::Float(f64::NAN));
+    }
+
+    #[test]
+    fn ord_test() {
+        assert_eq!(Number::Float(f64::NAN), Number::Float(-f64::NAN));
+        assert_ne!(Number::Float(f64::NAN), Number::Float(f64::INFINITY));
+        let mut numbers = [
+            Number::Float(2.5),
+            Number::Float(f64::NAN),
+            Number::Int(-3),
+            Number::Float(f64::INFINITY),
+            Number::Int(2),
+            Number::Float(-0.5),
+            Number::Float(f64::NEG_INFINITY),
+            Number::Int(i64::MAX),
+        ];
+        numbers.sort();
+        let sorted: Vec<String> = numbers.iter().map(Number::to_string).collect();
+        assert_eq!(sorted, ["-inf", "-3", "-0.5", "2", "2.5", "9223372036854775807", "inf", "NaN"]);
+        assert_eq!(Number::Int(1).cmp(&Number::Float(1.0)), Ordering::Equal);
+        assert_eq!(Number::Float(-0.0).cmp(&Number::Float(0.0)), Ordering::Equal);
+    }
+
+    #[test]
+    fn hash_test() {
+        assert_eq!(hash_of(Number::Int(1)), hash_of(Number::Float(1.0)));
+        assert_eq!(hash_of(Number::Int(-42)), hash_of(Number::Float(-42.0)));
+        assert_eq!(hash_of(Number::Int(0)), hash_of(Number::Float(-0.0)));
+        assert_eq!(hash_of(Number::Int(i64::MIN)), hash_of(Number::Float(-I64_BOUND)));
+        assert_eq!(hash_of(Number::Float(2.5)), hash_of(Number::Float(2.5)));
+        assert_eq!(hash_of(Number::Float(f64::NAN)), hash_of(Number::Float(-f64::NAN)));
+        assert_ne!(hash_of(Number::Float(2.5)), hash_of(Number::Int(2)));
+    }
+
+    #[test]
+    fn big_test() {
+        let big = || Number::Big(String::from("-123456789012345678901234567890"));
+        assert_eq!(big(), big());
+        assert_eq!(hash_of(big()), hash_of(big()));
+        assert_ne!(big(), Number::Float(-1.2345678901234568e29));
+        assert!(big() < Number::Int(i64::MIN));
+        assert!(Number::Float(-1e30) < big());
+        // The Float is the closest to the Big, but is exactly -123456789012345677877719597056.
+        assert!(big() < Number::Float(-1.2345678901234568e29));
+        let other = Number::Big(String::from("-123456789012345678901234567891"));
+        assert!(other < big() && other != big());
+        let positive = |text: &str| Number::Big(String::from(text));
+        assert!(positive("123456789012345678901234567891") > positive("123456789012345678901234567890"));
+        assert_eq!(positive("-0"), positive("0"));
+        // Numerically equal text is equal.
+        assert_eq!(positive("0099999999999999999999"), positive("99999999999999999999"));
+        assert_eq!(positive("+99999999999999999999"), positive("99999999999999999999"));
+        assert_eq!(hash_of(positive("0099999999999999999999")), hash_of(positive("99999999999999999999")));
+        assert_eq!(normalize_big("+0099999999999999999999"), "99999999999999999999");
+        assert_eq!(normalize_big("-0099999999999999999999"), "-99999999999999999999");
+        assert_eq!(normalize_big("-000"), "0");
+        assert_eq!(big().as_f64(), -1.2345678901234568e29);
+        let big_text = |text: &str| Number::Big(String::from(text));
+        assert!(big_text("-9223372036854775809") < Number::Int(i64::MIN));
+        assert!(big_text("-9223372036854775809") < Number::Float(-I64_BOUND));
+        assert!(big_text("9223372036854775808") > Number::Int(i64::MAX));
+        assert!(big_text("9223372036854775807") < Number::Float(I64_BOUND));
+        assert!(big_text("-18446744073709551617") < Number::Float(-18446744073709551616.0));
+        assert!(big_text("-18446744073709551615") > Number::Float(-18446744073709551616.0));
+        assert!(big_text("-1") < Number::Float(-0.5) && big_text("0") > Number::Float(-0.5));
+        assert!(big_text("-1") > Number::Float(f64::NEG_INFINITY) && big_text("1") < Number::Float(f64::INFINITY));
+        assert!(big_text("1") < Number::Float(f64::NAN));
+        // Equal values of different variants break the tie the same way each time.
+        assert!(big_text("18446744073709551616") > Number::Float(18446744073709551616.0));
+        assert!(Number::Float(18446744073709551616.0) < big_text("18446744073709551616"));
+        assert!(big_text("-9223372036854775808") > Number::Int(i64::MIN));
+    }
+
+    #[test]
+    fn arithmetic_test() {
+        assert!(matches!(Number::Int(2).checked_add(&Number::Int(3)), Some(Number::Int(5))));
+        assert!(matches!(Number::Int(2).checked_sub(&Number::Int(3)), Some(Number::Int(-1))));
+        assert!(matches!(Number::Int(2).checked_mul(&Number::Int(3)), Some(Number::Int(6))));
+        assert!(matches!(Number::Int(2).checked_add(&Number::Float(0.5)), Some(Number::Float(2.5))));
+        assert!(matches!(Number::Float(1.5).checked_mul(&Number::Int(2)), Some(Number::Float(3.0))));
+        assert!(Number::Int(i64::MAX).checked_add(&Number::Int(1)).is_none());
+        assert!(Number::Int(i64::MIN).checked_sub(&Number::Int(1)).is_none());
+        assert!(Number::Int(i64::MAX).checked_mul(&Number::Int(2)).is_none());
+        assert!(Number::Big(String::from("99999999999999999999")).checked_add(&Number::Int(1)).is_none());
+
+        assert!(matches!(Number::Int(2) + Number::Int(3), Number::Int(5)));
+        assert!(matches!(Number::Int(2) - Number::Float(0.5), Number::Float(1.5)));
+        assert!(matches!(Number::Int(i64::MAX) + Number::Int(1), Number::Float(float) if float == 9223372036854775808.0));
+        assert!(matches!(Number::Int(i64::MAX) * Number::Int(-2), Number::Float(float) if float == -18446744073709551614.0));
+        assert!(matches!(Number::Big(String::from("1e3")) * Number::Int(2), Number::Float(2000.0)));
+    }
+}
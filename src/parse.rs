@@ -1,8 +1,12 @@
 // Why did the digital archaeologist get excited about old software?
 // Because they loved discovering ancient "bits" of history!
+use std::num::IntErrorKind;
 use std::str::FromStr;
+use std::sync::Arc;
 
-use crate::{error::ParseError, Value, ValueMap, Number};
+use crate::{error::{ParseError, Warning}, number::normalize_big, pointer::escape_token, Value, ValueMap, Number};
+#[cfg(feature = "arena")]
+use crate::arena::{self, Arena};
 
 /// Result returned from JSON parsing.
 pub type ParseResult<T> = Result<T, ParseError>;
@@ -21,16 +25,33 @@ fn hex_value(chr: char) -> Option<u16> {
 }
 
 /// Unescape a string.
+///
+/// This is the inverse of the escaping used when writing a [Value::String], so unescaping
+/// the contents of a written string always gives back the original, including trailing
+/// backslashes. Unknown escapes such as `\x` unescape to the escaped character.
+/// The index of a [ParseError::InvalidEscapeSequence] is the byte index in `string`.
 pub fn unescape_string<S: AsRef<str>>(string: S) -> ParseResult<String> {
-    let s = string.as_ref();
+    unescape(string.as_ref(), false)
+}
+
+/// Unescape a string like [unescape_string]. If `strict` is set, only the escapes that are
+/// allowed in JSON (`\"`, `\\`, `\/`, `\b`, `\f`, `\n`, `\r`, `\t`, and `\u`) are accepted, and
+/// any other escape fails with [ParseError::InvalidEscapeSequence].
+fn unescape(s: &str, strict: bool) -> ParseResult<String> {
     let mut buffer = String::with_capacity(s.len());
-    let mut chars = s.chars();
-    while let Some(c) = chars.next() {
+    unescape_into(s, strict, &mut buffer)?;
+    Ok(buffer)
+}
+
+/// Unescape a string like [unescape], appending it to `buffer`.
+fn unescape_into(s: &str, strict: bool, buffer: &mut String) -> ParseResult<()> {
+    let mut chars = s.char_indices();
+    while let Some((index, c)) = chars.next() {
         if c != '\\' {
             buffer.push(c);
             continue;
         }
-        buffer.push(match chars.next() {
+        buffer.push(match chars.next().map(|(_, c)| c) {
             Some('f') => '\u{000c}',
             Some('b') => '\u{0008}',
             Some('n') => '\n',
@@ -40,7 +61,7 @@ pub fn unescape_string<S: AsRef<str>>(string: S) -> ParseResult<String> {
                 // Read 4 hex-digits
                 let mut hex: u16 = 0;
                 for i in 0..4 {
-                    let Some(digit) = chars.next() else {
+                    let Some((_, digit)) = chars.next() else {
                         return Err(ParseError::UnexpectedEOF);
                     };
                     let Some(value) = hex_value(digit) else {
@@ -53,33 +74,205 @@ pub fn unescape_string<S: AsRef<str>>(string: S) -> ParseResult<String> {
                     }
                 }
                 let Some(res) = char::from_u32(hex as u32) else {
-                    return Err(ParseError::InvalidEscapeSequence);
+                    return Err(ParseError::InvalidEscapeSequence { index });
                 };
                 res
             }
             // If the character is any other character, just return the character.
             // This allows to unescape \< to < without having to be explicit.
             // Also, I just think it's a good idea to unescape any character.
-            Some(other) => other,
+            Some(other) if !strict || matches!(other, '"' | '\\' | '/') => other,
+            Some(_) => return Err(ParseError::InvalidEscapeSequence { index }),
             None => return Err(ParseError::UnexpectedEOF),
         });
     }
-    Ok(buffer)
+    Ok(())
+}
+
+/// Get the 1-based line and column of the byte `index` in `source`, such as the index of a
+/// [ParseError]. `\r\n` counts as a single line break, as does a lone `\r` or `\n`, and
+/// columns count characters rather than bytes.
+pub fn line_column(source: &str, index: usize) -> (usize, usize) {
+    let bytes = &source.as_bytes()[..index.min(source.len())];
+    let (mut line, mut column) = (1, 1);
+    for (i, &byte) in bytes.iter().enumerate() {
+        match byte {
+            // The line break was already counted at the '\r'.
+            b'\n' if i > 0 && bytes[i - 1] == b'\r' => (),
+            b'\r' | b'\n' => {
+                line += 1;
+                column = 1;
+            }
+            // UTF-8 continuation bytes are part of the previous character.
+            _ if byte & 0b1100_0000 == 0b1000_0000 => (),
+            _ => column += 1,
+        }
+    }
+    (line, column)
+}
+
+/// The default [ParseOptions::max_depth].
+pub const DEFAULT_MAX_DEPTH: usize = 128;
+
+/// Options that control how JSON text is parsed. The default options are strict.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// Accept some common non-standard syntax:
+    /// * `=` or `=>` as the separator between keys and values in objects.
+    /// * JavaScript-style hexadecimal (`0xFF`), octal (`0o17`), and binary (`0b1010`) integers.
+    /// * `//` line comments and `/* */` block comments wherever whitespace is allowed.
+    pub lenient: bool,
+    /// The maximum length of a string (including object keys) in bytes of source text,
+    /// before unescaping. Parsing fails with [ParseError::StringTooLong] as soon as a
+    /// longer string is found.
+    ///
+    /// This counts the source bytes between the quotes, not the length of the unescaped
+    /// [String], so an escape sequence counts as all of its bytes: `\n` is two bytes and
+    /// `\u00e9` is six, although each unescapes to a single character.
+    pub max_string_length: Option<usize>,
+    /// The maximum number of arrays and objects that can be nested inside each other.
+    /// Parsing fails with [ParseError::DepthLimitExceeded] when it is exceeded. The default
+    /// is [DEFAULT_MAX_DEPTH].
+    ///
+    /// The parser doesn't use recursion, so any depth can be parsed, but dropping, cloning,
+    /// comparing, and writing a [Value] are recursive, so a very deeply nested [Value] can
+    /// overflow the stack when it is dropped. Only raise the limit (or set it to `None`) for
+    /// trusted input.
+    pub max_depth: Option<usize>,
+    /// Collect the values of keys that appear more than once in the same object into a
+    /// [Value::Array] in the order they appear, such as for multi-valued keys. By default,
+    /// the last value wins.
+    pub collect_duplicate_keys: bool,
+    /// Keep integers that don't fit in an [i64] as [Number::Big] with their exact text,
+    /// instead of failing with [ParseError::ParseIntError].
+    pub preserve_big_integers: bool,
+    /// The maximum number of entries (distinct keys) in a single object. Parsing fails with
+    /// [ParseError::LimitExceeded] as soon as an object grows past it, which guards against
+    /// pathologically large maps and hash flooding.
+    pub max_object_entries: Option<usize>,
+    /// A function that every object key is passed through after it is unescaped, such as
+    /// to lowercase keys for case-insensitive configuration. Keys that are the same after
+    /// normalization are duplicates, so they can be collected with
+    /// [ParseOptions::collect_duplicate_keys].
+    pub key_normalizer: Option<KeyNormalizer>,
+    /// The maximum number of digits in a number, counting the integer part, the fraction,
+    /// and the exponent. Parsing fails with [ParseError::NumberTooLong] as soon as a number
+    /// has more, which bounds the work done converting very long numbers.
+    pub max_number_digits: Option<usize>,
+    /// Only accept the escape sequences that are allowed in JSON strings (`\"`, `\\`, `\/`,
+    /// `\b`, `\f`, `\n`, `\r`, `\t`, and `\u`). Any other escape fails with
+    /// [ParseError::InvalidEscapeSequence]. By default, unknown escapes such as `\x`
+    /// unescape to the escaped character.
+    pub strict_escapes: bool,
+    /// Fail with [ParseError::InvalidCharacter] if there is whitespace before or after the
+    /// value, instead of skipping it, for protocols where the body must be exactly one JSON
+    /// value. A leading byte order mark is still skipped. This doesn't apply to
+    /// [StreamParser], where whitespace separates the values.
+    pub disallow_surrounding_whitespace: bool,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            lenient: false,
+            max_string_length: None,
+            max_depth: Some(DEFAULT_MAX_DEPTH),
+            collect_duplicate_keys: false,
+            preserve_big_integers: false,
+            max_object_entries: None,
+            key_normalizer: None,
+            max_number_digits: None,
+            strict_escapes: false,
+            disallow_surrounding_whitespace: false,
+        }
+    }
+}
+
+/// A function or closure that object keys are passed through while parsing.
+/// See [ParseOptions::key_normalizer].
+#[derive(Clone)]
+pub struct KeyNormalizer(pub Arc<dyn Fn(&str) -> String + Send + Sync>);
+
+impl KeyNormalizer {
+    /// Create a [KeyNormalizer] from a function or closure.
+    pub fn new<F: Fn(&str) -> String + Send + Sync + 'static>(normalizer: F) -> Self {
+        Self(Arc::new(normalizer))
+    }
+}
+
+impl std::fmt::Debug for KeyNormalizer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("KeyNormalizer").finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for KeyNormalizer {
+    /// Normalizers are only equal if they are clones of the same [KeyNormalizer].
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
 }
 
+impl Eq for KeyNormalizer {}
+
 /// A JSON parser.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 struct Parser<'a> {
-    source: &'a str,
+    source: &'a [u8],
+    /// Whether `source` is known to be valid UTF-8 (it came from a `&str`). Otherwise, only
+    /// the text that is extracted from it (strings and numbers) is validated, as it is read.
+    utf8: bool,
     index: usize,
+    options: ParseOptions,
+    /// JSON Pointers to the keys that appeared more than once in the same object, if they
+    /// are being reported. See [Value::parse_with_duplicate_report].
+    duplicate_keys: Option<Vec<String>>,
+    /// The [Warning]s found so far, if they are being collected. See [Value::parse_lenient].
+    warnings: Option<Vec<Warning>>,
+    /// Whether more input may follow the end of `source`, as in a [StreamParser]. If so, a
+    /// number or keyword at the end of the source fails with [ParseError::UnexpectedEOF],
+    /// since it may not be complete.
+    partial: bool,
+    /// Where [Parser::parse_nested] can continue after an [ParseError::UnexpectedEOF].
+    checkpoint: Checkpoint,
+}
+
+/// A position in the source where parsing can continue, once more input is available.
+#[derive(Debug, Default, Clone, Copy)]
+struct Checkpoint {
+    index: usize,
+    /// Whether the latest value in the innermost open container is already parsed, so that
+    /// parsing continues with the separator after it.
+    after_value: bool,
 }
 
 impl<'a> Parser<'a> {
     /// Create a new [Parser] from a `source` string.
-    fn new(source: &'a str) -> Self {
+    fn new(source: &'a str, options: ParseOptions) -> Self {
+        Self {
+            utf8: true,
+            ..Self::from_slice(source.as_bytes(), options)
+        }
+    }
+
+    /// Create a new [Parser] from `source` bytes, which are validated as UTF-8 as they are read.
+    fn from_slice(source: &'a [u8], options: ParseOptions) -> Self {
         Self {
             source,
+            utf8: false,
             index: 0,
+            options,
+            duplicate_keys: None,
+            warnings: None,
+            partial: false,
+            checkpoint: Checkpoint::default(),
+        }
+    }
+
+    /// Record a [Warning] if warnings are being collected.
+    fn warn(&mut self, warning: Warning) {
+        if let Some(warnings) = &mut self.warnings {
+            warnings.push(warning);
         }
     }
 
@@ -91,7 +284,7 @@ impl<'a> Parser<'a> {
     /// Takes a look at the next byte in the stream without advancing the index.
     fn peek(&self) -> Option<u8> {
         if self.index < self.source.len() {
-            Some(self.source.as_bytes()[self.index])
+            Some(self.source[self.index])
         } else {
             None
         }
@@ -100,7 +293,7 @@ impl<'a> Parser<'a> {
     /// Retrieve the next byte paired with its index, advancing the parser in the process.
     fn indexed_next(&mut self) -> Option<(usize, u8)> {
         if self.index < self.source.len() {
-            let res = Some((self.index, self.source.as_bytes()[self.index]));
+            let res = Some((self.index, self.source[self.index]));
             self.index += 1;
             res
         } else {
@@ -111,7 +304,7 @@ impl<'a> Parser<'a> {
     /// Retrieve the next byte, advancing the parser in the process.
     fn next(&mut self) -> Option<u8> {
         if self.index < self.source.len() {
-            let res = Some(self.source.as_bytes()[self.index]);
+            let res = Some(self.source[self.index]);
             self.index += 1;
             res
         } else {
@@ -126,27 +319,77 @@ impl<'a> Parser<'a> {
 
     /// Decrement the index by one.
     fn rewind(&mut self) {
-        self.index = self.index.checked_sub(1).unwrap_or(0);
+        self.index = self.index.saturating_sub(1);
     }
 
     /// Checks if the parser matches text at the current index.
     fn matches<S: AsRef<str>>(&mut self, text: S) -> bool {
         let s = text.as_ref();
         if self.index + s.len() <= self.source.len() {
-            self.source[self.index..].starts_with(s)
+            self.source[self.index..].starts_with(s.as_bytes())
         } else {
             false
         }
     }
 
-    /// Consumes all whitespace, advancing the index.
-    fn eat_whitespace(&mut self) {
-        while let Some(peek) = self.peek() {
-            if peek.is_ascii_whitespace() {
+    /// Get the source text in `start..end`, validating it as UTF-8 if that isn't known already.
+    /// `start` and `end` must each be at the source boundaries or next to an ASCII character.
+    fn text(&self, start: usize, end: usize) -> ParseResult<&'a str> {
+        let bytes = &self.source[start..end];
+        if self.utf8 {
+            // SAFETY: The source came from a `&str`, and `start` and `end` are next to ASCII
+            // characters (or at the ends), so they are character boundaries.
+            return Ok(unsafe { std::str::from_utf8_unchecked(bytes) });
+        }
+        std::str::from_utf8(bytes).map_err(|err| ParseError::InvalidUtf8 { index: start + err.valid_up_to() })
+    }
+
+    /// Consumes all whitespace, advancing the index. With [ParseOptions::lenient], `//` and
+    /// `/* */` comments are consumed too.
+    fn eat_whitespace(&mut self) -> ParseResult<()> {
+        loop {
+            while self.peek().is_some_and(|peek| peek.is_ascii_whitespace()) {
                 self.advance(1);
-            } else {
-                break;
             }
+            if !self.options.lenient || self.peek() != Some(b'/') {
+                return Ok(());
+            }
+            self.eat_comment()?;
+        }
+    }
+
+    /// Consume the `//` comment (up to the line break) or `/* */` comment at the index.
+    fn eat_comment(&mut self) -> ParseResult<()> {
+        let start = self.index;
+        let rest = &self.source[start..];
+        let len = match rest.get(1) {
+            Some(b'/') => match rest.iter().position(|&byte| matches!(byte, b'\n' | b'\r')) {
+                Some(len) => len,
+                // More of the comment may follow.
+                None if self.partial => return Err(ParseError::UnexpectedEOF),
+                None => rest.len(),
+            },
+            Some(b'*') => match rest[2..].windows(2).position(|pair| pair == b"*/") {
+                Some(len) => len + 4,
+                None => return Err(ParseError::UnexpectedEOF),
+            },
+            None if self.partial => return Err(ParseError::UnexpectedEOF),
+            _ => return Err(ParseError::InvalidCharacter(start)),
+        };
+        self.warn(Warning::Comment { index: start });
+        self.advance(len);
+        Ok(())
+    }
+
+    /// The error for a keyword that doesn't match at the current index, which is
+    /// [ParseError::UnexpectedEOF] if the rest of a [Parser::partial] source is the start
+    /// of one of the `keywords`.
+    fn keyword_error(&self, keywords: &[&str]) -> ParseError {
+        let rest = &self.source[self.index..];
+        if self.partial && keywords.iter().any(|keyword| keyword.as_bytes().starts_with(rest)) {
+            ParseError::UnexpectedEOF
+        } else {
+            ParseError::InvalidCharacter(self.index)
         }
     }
 
@@ -156,7 +399,7 @@ impl<'a> Parser<'a> {
             self.advance(4);
             Ok(Value::Null)
         } else {
-            Err(ParseError::InvalidCharacter(self.index))
+            Err(self.keyword_error(&["null"]))
         }
     }
 
@@ -169,23 +412,46 @@ impl<'a> Parser<'a> {
             self.advance(5);
             Ok(false)
         } else {
-            Err(ParseError::InvalidCharacter(self.index))
+            Err(self.keyword_error(&["true", "false"]))
         }
     }
 
     /// Parse a [Number].
     fn parse_number(&mut self) -> ParseResult<Number> {
-        // Valid characters that can follow a number: '}', ']', ',', and whitespace.
+        // Valid characters that can follow a number: '}', ']', ',', whitespace, and the '/'
+        // of a comment with [ParseOptions::lenient].
         let mut found_e = false;
         let mut found_dot = false;
         let mut found_num = false;
+        let mut digits = 0;
         let start = self.index;
-        if let Some(b'-' | b'+') = self.peek() {
-            self.next();
+        match self.peek() {
+            Some(b'-') => self.advance(1),
+            Some(b'+') => {
+                self.warn(Warning::PlusSign { index: start });
+                self.advance(1);
+            }
+            _ => (),
         }
-        while let Some((index, next)) = self.indexed_next() {
+        if self.options.lenient {
+            if let Some(number) = self.parse_radix_integer(start)? {
+                self.warn(Warning::RadixInteger { index: start });
+                return Ok(number);
+            }
+        }
+        loop {
+            let Some((index, next)) = self.indexed_next() else {
+                if self.partial {
+                    return Err(ParseError::UnexpectedEOF);
+                }
+                break;
+            };
             match next {
-                b'0'..=b'9' => found_num = true,
+                b'0'..=b'9' => {
+                    found_num = true;
+                    digits += 1;
+                    self.check_number_digits(digits, index)?;
+                }
                 b'.' if found_num && !found_dot && !found_e => found_dot = true,
                 b'e' | b'E' if found_num && !found_e => {
                     found_e = true;
@@ -201,20 +467,73 @@ impl<'a> Parser<'a> {
                     self.rewind();
                     break
                 },
+                b'/' if self.options.lenient => {
+                    self.rewind();
+                    break
+                },
                 _ => return Err(ParseError::InvalidCharacter(index)),
             }
         }
         if self.index - start != 0 {
             if found_dot | found_e {
-                Ok(Number::Float(self.source[start..self.index].parse::<f64>()?))
+                Ok(Number::Float(self.text(start, self.index)?.parse::<f64>()?))
             } else {
-                Ok(Number::Int(self.source[start..self.index].parse::<i64>()?))
+                let text = self.text(start, self.index)?;
+                match text.parse::<i64>() {
+                    Ok(int) => Ok(Number::Int(int)),
+                    Err(err) if self.options.preserve_big_integers && matches!(
+                        err.kind(),
+                        IntErrorKind::PosOverflow | IntErrorKind::NegOverflow,
+                    ) => Ok(Number::Big(normalize_big(text))),
+                    Err(err) => Err(err.into()),
+                }
             }
         } else {
             Err(ParseError::InvalidCharacter(self.index))
         }
     }
 
+    /// Check the number of digits (`count`) in a number against
+    /// [ParseOptions::max_number_digits]. `index` is the index of the last digit.
+    fn check_number_digits(&self, count: usize, index: usize) -> ParseResult<()> {
+        if self.options.max_number_digits.is_some_and(|max| count > max) {
+            return Err(ParseError::NumberTooLong { index });
+        }
+        Ok(())
+    }
+
+    /// Parse a JavaScript-style `0x`, `0o`, or `0b` integer literal, or return `None` if the
+    /// number doesn't start with one of those prefixes. `start` is the index of the sign, if
+    /// there is one. Only used with [ParseOptions::lenient].
+    fn parse_radix_integer(&mut self, start: usize) -> ParseResult<Option<Number>> {
+        let radix = match self.source.get(self.index..self.index + 2) {
+            Some(b"0x" | b"0X") => 16,
+            Some(b"0o" | b"0O") => 8,
+            Some(b"0b" | b"0B") => 2,
+            _ => return Ok(None),
+        };
+        self.advance(2);
+        let digits_start = self.index;
+        while self.peek().is_some_and(|byte| (byte as char).is_digit(radix)) {
+            self.check_number_digits(self.index - digits_start + 1, self.index)?;
+            self.next();
+        }
+        match self.peek() {
+            None if self.partial => return Err(ParseError::UnexpectedEOF),
+            None | Some(b'}' | b']' | b',') if self.index > digits_start => (),
+            Some(ws) if ws.is_ascii_whitespace() && self.index > digits_start => (),
+            Some(b'/') if self.index > digits_start => (),
+            _ => return Err(ParseError::InvalidCharacter(self.index)),
+        }
+        let digits = self.text(digits_start, self.index)?;
+        let int = if self.source[start] == b'-' {
+            i64::from_str_radix(&format!("-{digits}"), radix)?
+        } else {
+            i64::from_str_radix(digits, radix)?
+        };
+        Ok(Some(Number::Int(int)))
+    }
+
     /// Parse a string between double quotes (`"`).
     /// 
     /// The following characters must be escaped:  
@@ -234,150 +553,1301 @@ impl<'a> Parser<'a> {
     /// "Hello, world!"
     /// ```
     fn parse_string(&mut self) -> ParseResult<String> {
+        let raw = self.parse_raw_string()?;
+        self.unescape(raw)
+    }
+
+    /// Unescape the `raw` text of the string that was just parsed, checking
+    /// [ParseOptions::strict_escapes].
+    fn unescape(&self, raw: &str) -> ParseResult<String> {
+        let mut buffer = String::with_capacity(raw.len());
+        self.unescape_into(raw, &mut buffer)?;
+        Ok(buffer)
+    }
+
+    /// Unescape the `raw` text of the string that was just parsed like [Parser::unescape],
+    /// appending it to `buffer`.
+    fn unescape_into(&self, raw: &str, buffer: &mut String) -> ParseResult<()> {
+        // The index is just past the closing quote.
+        let start = self.index - 1 - raw.len();
+        unescape_into(raw, self.options.strict_escapes, buffer).map_err(|err| match err {
+            ParseError::InvalidEscapeSequence { index } => ParseError::InvalidEscapeSequence { index: start + index },
+            err => err,
+        })
+    }
+
+    /// Parse a string between double quotes (`"`) without unescaping it.
+    /// Returns the source text between the quotes.
+    fn parse_raw_string(&mut self) -> ParseResult<&'a str> {
         match self.peek() {
             Some(b'"') => { self.next(); }
             Some(_) => { return Err(ParseError::InvalidCharacter(self.index)); }
             None => { return Err(ParseError::UnexpectedEOF); }
         }
         let start = self.index;
-        let string = loop {
+        loop {
             let Some((index, next)) = self.indexed_next() else {
                 return Err(ParseError::UnexpectedEOFWhileParsingString(start));
             };
             match next {
                 // Strings should not contain new-lines.
                 b'\n' | b'\r' => { return Err(ParseError::LineBreakWhileParsingString(index)); }
-                b'"' => break unescape_string(&self.source[start..index])?,
+                b'"' => return self.text(start, index),
                 b'\\' => { self.advance(1); }
                 _ => {}
             }
-        };
-        Ok(string)
+            // The read bytes include the escaped byte after a backslash.
+            if let Some(max) = self.options.max_string_length.filter(|&max| self.index - start > max) {
+                return Err(ParseError::StringTooLong { index: start + max });
+            }
+        }
     }
 
-    /// Parse a JSON Array (JSON values in comma separated list between `[` and `]`).  
-    /// Example:
-    /// ```json
-    /// [
-    ///     true,
-    ///     false,
-    ///     null,
-    ///     3.14,
-    ///     "Hello, world!",
-    ///     [1, 2, 3],
-    ///     {
-    ///         "example" : "The quick brown fox jumps over the lazy dog."
-    ///     }
-    /// ]
-    /// ```
-    fn parse_array(&mut self) -> ParseResult<Vec<Value>> {
+    /// Parse an object key, passing it through [ParseOptions::key_normalizer] if there is one.
+    fn parse_key(&mut self) -> ParseResult<String> {
+        let key = self.parse_string()?;
+        Ok(match &self.options.key_normalizer {
+            Some(KeyNormalizer(normalizer)) => normalizer(&key),
+            None => key,
+        })
+    }
+
+    /// Open an array or object at the current index, checking [ParseOptions::max_depth].
+    /// `depth` is the number of containers that are already open.
+    fn open_container(&mut self, depth: usize) -> ParseResult<()> {
+        if self.options.max_depth.is_some_and(|max| depth >= max) {
+            return Err(ParseError::DepthLimitExceeded { index: self.index });
+        }
+        self.advance(1);
+        Ok(())
+    }
+
+    /// Checks if there is another element in an array after `[` or `,`, consuming the
+    /// closing `]` if there isn't.
+    fn array_continues(&mut self) -> ParseResult<bool> {
+        self.eat_whitespace()?;
+        match self.peek() {
+            Some(b']') => {
+                self.advance(1);
+                Ok(false)
+            }
+            Some(_) => Ok(true),
+            None => Err(ParseError::UnexpectedEOF),
+        }
+    }
+
+    /// Checks if there is another entry in an object after `{` or `,`, consuming the
+    /// closing `}` if there isn't. If there is, the index is at the key.
+    fn object_continues(&mut self) -> ParseResult<bool> {
+        self.eat_whitespace()?;
+        match self.peek() {
+            Some(b'"') => Ok(true),
+            Some(b'}') => {
+                self.advance(1);
+                Ok(false)
+            }
+            Some(_) => Err(ParseError::InvalidCharacter(self.index)),
+            None => Err(ParseError::UnexpectedEOF),
+        }
+    }
+
+    /// Parse the separator between a key and its value, and the whitespace around it.
+    fn parse_key_separator(&mut self) -> ParseResult<()> {
+        self.eat_whitespace()?;
         match self.indexed_next() {
-            Some((_, b'[')) => (),
+            Some((_, b':')) => (),
+            Some((index, b'=')) if self.options.lenient => {
+                self.warn(Warning::KeySeparator { index });
+                match self.peek() {
+                    Some(b'>') => self.advance(1),
+                    None if self.partial => return Err(ParseError::UnexpectedEOF),
+                    _ => (),
+                }
+            }
             Some((index, _)) => return Err(ParseError::InvalidCharacter(index)),
             None => return Err(ParseError::UnexpectedEOF),
         }
-        let mut array = Vec::new();
-        loop {
-            self.eat_whitespace();
-            match self.peek() {
-                Some(b']') => {
-                    self.advance(1);
-                    break;
-                }
-                Some(_) => {
-                    array.push(self.parse_value()?);
-                    self.eat_whitespace();
-                    match self.indexed_next() {
-                        Some((_, b']')) => break,
-                        Some((_, b',')) => continue,
-                        Some((index, _)) => return Err(ParseError::InvalidCharacter(index)),
-                        None => return Err(ParseError::UnexpectedEOF),
-                    }
+        self.eat_whitespace()?;
+        Ok(())
+    }
+
+    /// Parse the key and separator of the next entry in an object after `{` or `,`, or
+    /// consume the closing `}` and return `None` if there are no more entries.
+    fn parse_entry_key(&mut self) -> ParseResult<Option<String>> {
+        if !self.object_continues()? {
+            return Ok(None);
+        }
+        let key = self.parse_key()?;
+        self.parse_key_separator()?;
+        Ok(Some(key))
+    }
+
+    /// Parse the separator after an element of an array. Returns `true` if it was the end of
+    /// the array.
+    fn array_closes(&mut self) -> ParseResult<bool> {
+        self.eat_whitespace()?;
+        match self.indexed_next() {
+            Some((index, b',')) => {
+                let closed = !self.array_continues()?;
+                if closed {
+                    self.warn(Warning::TrailingComma { index });
                 }
-                None => return Err(ParseError::UnexpectedEOF),
+                Ok(closed)
             }
+            Some((_, b']')) => Ok(true),
+            Some((index, _)) => Err(ParseError::InvalidCharacter(index)),
+            None => Err(ParseError::UnexpectedEOF),
         }
-        Ok(array)
     }
 
-    /// Parse a JSON Object.
-    /// 
-    /// #### Example:
-    /// ```json
-    /// {
-    ///     "null" : null,
-    ///     "boolean_array" : [false, true],
-    ///     "number" : 3.14159265358979,
-    ///     "string" : "Hello, world!",
-    /// }
-    /// ```
-    fn parse_object(&mut self) -> ParseResult<ValueMap> {
+    /// Parse the separator after an entry of an object. Returns `true` if it was the end of
+    /// the object, or `false` if the index is at the key of the next entry.
+    fn object_closes(&mut self) -> ParseResult<bool> {
+        self.eat_whitespace()?;
         match self.indexed_next() {
-            Some((_, b'{')) => (),
-            Some((index, _)) => return Err(ParseError::InvalidCharacter(index)),
-            None => return Err(ParseError::UnexpectedEOF),
-        }
-        let mut map = ValueMap::new();
-        loop {
-            self.eat_whitespace();
-            match self.peek() {
-                Some(b'"') => {
-                    let key = self.parse_string()?;
-                    self.eat_whitespace();
-                    match self.indexed_next() {
-                        Some((_, b':')) => (),
-                        Some((index, _)) => return Err(ParseError::InvalidCharacter(index)),
-                        None => return Err(ParseError::UnexpectedEOF),
-                    }
-                    self.eat_whitespace();
-                    let value = self.parse_value()?;
-                    map.insert(key, value);
-                    self.eat_whitespace();
-                    match self.indexed_next() {
-                        Some((_, b',')) => continue,
-                        Some((_, b'}')) => break,
-                        Some((index, _)) => return Err(ParseError::InvalidCharacter(index)),
-                        None => return Err(ParseError::UnexpectedEOF),
-                    }
+            Some((index, b',')) => {
+                let closed = !self.object_continues()?;
+                if closed {
+                    self.warn(Warning::TrailingComma { index });
                 }
-                Some(b'}') => {
-                    self.next();
-                    break;
+                Ok(closed)
+            }
+            Some((_, b'}')) => Ok(true),
+            Some((index, _)) => Err(ParseError::InvalidCharacter(index)),
+            None => Err(ParseError::UnexpectedEOF),
+        }
+    }
+
+    /// Insert the entry that was just parsed into `object`, checking
+    /// [ParseOptions::max_object_entries].
+    fn insert_entry(&self, object: &mut PartialObject, value: Value) -> ParseResult<()> {
+        let key = std::mem::take(&mut object.key);
+        if self.options.collect_duplicate_keys {
+            if let Some(existing) = object.map.get_mut(&key) {
+                if object.collected.contains(&key) {
+                    existing.push(value);
+                } else {
+                    *existing = Value::Array(vec![std::mem::replace(existing, Value::Null), value]);
+                    object.collected.insert(key);
                 }
-                Some(_) => return Err(ParseError::InvalidCharacter(self.index)),
-                None => return Err(ParseError::UnexpectedEOF),
+                return Ok(());
             }
         }
-        Ok(map)
+        object.map.insert(key, value);
+        if self.options.max_object_entries.is_some_and(|max| object.map.len() > max) {
+            return Err(ParseError::LimitExceeded { index: self.index });
+        }
+        Ok(())
     }
 
-    /// Parse a JSON Value.
-    fn parse_value(&mut self) -> ParseResult<Value> {
+    /// Parse the whole source as a single [Value], skipping a leading byte order mark and
+    /// surrounding whitespace.
+    fn parse_document(&mut self) -> ParseResult<Value> {
+        self.start_document()?;
+        let res = self.parse_value()?;
+        self.end_document()?;
+        Ok(res)
+    }
+
+    /// Parse the whole source as a single object into `map`, like [Parser::parse_document].
+    /// `map` is cleared first, and its allocation is used for the entries. On error, `map` is
+    /// left empty, still with its allocation.
+    fn parse_object_into(&mut self, map: &mut ValueMap) -> ParseResult<()> {
+        map.clear();
+        let result = self.parse_object_entries(map).and_then(|()| self.end_document());
+        if result.is_err() {
+            map.clear();
+        }
+        result
+    }
+
+    /// Parse an object into the empty `map`. On error, `map` holds the entries parsed so far.
+    fn parse_object_entries(&mut self, map: &mut ValueMap) -> ParseResult<()> {
+        self.start_document()?;
+        match self.peek() {
+            Some(b'{') => self.open_container(0)?,
+            Some(_) => return Err(ParseError::InvalidCharacter(self.index)),
+            None => return Err(ParseError::UnexpectedEOF),
+        }
+        let Some(key) = self.parse_entry_key()? else {
+            return Ok(());
+        };
+        let mut stack = vec![Container::Object(PartialObject::with_map(std::mem::take(map), key))];
+        let result = self.parse_nested(&mut stack, false);
+        // Give the allocation back, whether the object was finished or not.
+        match (result, stack.into_iter().next()) {
+            (Ok(Value::Object(object)), _) => *map = object,
+            (Err(err), Some(Container::Object(object))) => {
+                *map = object.map;
+                return Err(err);
+            }
+            (Err(err), _) => return Err(err),
+            (Ok(_), _) => unreachable!(),
+        }
+        Ok(())
+    }
+
+    /// Skip a leading byte order mark and whitespace, or fail at the whitespace if
+    /// [ParseOptions::disallow_surrounding_whitespace] is set.
+    fn start_document(&mut self) -> ParseResult<()> {
+        if self.matches("\u{feff}") {
+            self.advance('\u{feff}'.len_utf8());
+        }
+        self.eat_surrounding_whitespace()
+    }
+
+    /// Skip trailing whitespace, failing if it isn't the end of the source.
+    fn end_document(&mut self) -> ParseResult<()> {
+        self.eat_surrounding_whitespace()?;
+        if !self.is_eof() {
+            Err(ParseError::InvalidCharacter(self.index))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Skip the whitespace before or after the document, checking
+    /// [ParseOptions::disallow_surrounding_whitespace].
+    fn eat_surrounding_whitespace(&mut self) -> ParseResult<()> {
+        if self.options.disallow_surrounding_whitespace {
+            if self.peek().is_some_and(|byte| byte.is_ascii_whitespace()) {
+                return Err(ParseError::InvalidCharacter(self.index));
+            }
+        } else {
+            self.eat_whitespace()?;
+        }
+        Ok(())
+    }
+
+    /// Parse a JSON Value that isn't an array or object.
+    fn parse_scalar(&mut self) -> ParseResult<Value> {
         Ok(match self.peek() {
             Some(b'n') => self.parse_null()?,
             Some(b't' | b'f') => Value::Boolean(self.parse_boolean()?),
             Some(b'+' | b'-' | b'0'..=b'9') => Value::Number(self.parse_number()?),
             Some(b'"') => Value::String(self.parse_string()?),
-            Some(b'[') => Value::Array(self.parse_array()?),
-            Some(b'{') => Value::Object(self.parse_object()?),
             Some(_) => return Err(ParseError::InvalidCharacter(self.index)),
             None => return Err(ParseError::UnexpectedEOF),
         })
     }
+
+    /// Parse a JSON Value.
+    ///
+    /// Arrays (JSON values in a comma separated list between `[` and `]`) and objects
+    /// (`"key" : value` pairs in a comma separated list between `{` and `}`) are parsed
+    /// with an explicit stack of open containers rather than by recursion, so deeply
+    /// nested documents can't overflow the call stack.
+    fn parse_value(&mut self) -> ParseResult<Value> {
+        self.parse_nested(&mut Vec::new(), false)
+    }
+
+    /// Parse the next value into the innermost container of `stack`, continuing until every
+    /// container in it is closed, and return the outermost value. See [Parser::parse_value].
+    /// If `after_value` is set, the innermost container already has its latest value, and
+    /// parsing continues with the separator after it.
+    ///
+    /// On error, `stack` is left with the containers that were still open, and
+    /// [Parser::checkpoint] is where parsing can be resumed once more input is available.
+    fn parse_nested(&mut self, stack: &mut Vec<Container>, mut after_value: bool) -> ParseResult<Value> {
+        'value: loop {
+            let mut value = if after_value {
+                None
+            } else {
+                // The whitespace before the value is usually eaten already, but not if
+                // parsing continues here after more input was added to the source.
+                self.checkpoint = Checkpoint { index: self.index, after_value: false };
+                self.eat_whitespace()?;
+                Some(match self.peek() {
+                    Some(b'[') => {
+                        self.open_container(stack.len())?;
+                        if self.array_continues()? {
+                            stack.push(Container::Array(Vec::new()));
+                            continue 'value;
+                        }
+                        Value::Array(Vec::new())
+                    }
+                    Some(b'{') => {
+                        self.open_container(stack.len())?;
+                        match self.parse_entry_key()? {
+                            Some(key) => {
+                                stack.push(Container::Object(PartialObject::new(key)));
+                                continue 'value;
+                            }
+                            None => Value::Object(ValueMap::new()),
+                        }
+                    }
+                    _ => self.parse_scalar()?,
+                })
+            };
+            after_value = false;
+            // Add the value to the innermost open container, closing containers as they end.
+            loop {
+                if let Some(value) = value.take() {
+                    if self.duplicate_keys.is_some() || self.warnings.is_some() {
+                        self.report_duplicate_key(stack);
+                    }
+                    let Some(container) = stack.last_mut() else {
+                        return Ok(value);
+                    };
+                    match container {
+                        Container::Array(array) => array.push(value),
+                        Container::Object(object) => self.insert_entry(object, value)?,
+                    }
+                }
+                self.checkpoint = Checkpoint { index: self.index, after_value: true };
+                let Some(container) = stack.last_mut() else {
+                    unreachable!();
+                };
+                if !self.container_closes(container)? {
+                    continue 'value;
+                }
+                value = match stack.pop() {
+                    Some(Container::Array(array)) => Some(Value::Array(array)),
+                    Some(Container::Object(object)) => Some(Value::Object(object.map)),
+                    None => unreachable!(),
+                };
+            }
+        }
+    }
+
+    /// Parse the separator after the latest value in `container`. Returns `true` if it was the
+    /// end of the container, or `false` if another value follows (after its key, in an object).
+    fn container_closes(&mut self, container: &mut Container) -> ParseResult<bool> {
+        match container {
+            Container::Array(_) => self.array_closes(),
+            Container::Object(_) if self.object_closes()? => Ok(true),
+            Container::Object(object) => {
+                object.key = self.parse_key()?;
+                self.parse_key_separator()?;
+                Ok(false)
+            }
+        }
+    }
+
+    /// Record a JSON Pointer to the key of the innermost open object if the object already
+    /// has that key.
+    fn report_duplicate_key(&mut self, stack: &[Container]) {
+        let Some(Container::Object(object)) = stack.last() else {
+            return;
+        };
+        if !object.map.contains_key(&object.key) {
+            return;
+        }
+        let pointer = stack.iter().fold(String::new(), |mut pointer, container| {
+            pointer.push('/');
+            match container {
+                // The value that is being parsed will be the next element.
+                Container::Array(array) => pointer.push_str(&array.len().to_string()),
+                Container::Object(object) => pointer.push_str(&escape_token(&object.key)),
+            }
+            pointer
+        });
+        if let Some(duplicate_keys) = &mut self.duplicate_keys {
+            duplicate_keys.push(pointer.clone());
+        }
+        self.warn(Warning::DuplicateKey { pointer });
+    }
+}
+
+/// An array or object that is still being parsed by [Parser::parse_value].
+#[derive(Debug, Clone)]
+enum Container {
+    Array(Vec<Value>),
+    Object(PartialObject),
+}
+
+/// An object that is still being parsed by [Parser::parse_value].
+#[derive(Debug, Clone)]
+struct PartialObject {
+    /// The entries parsed so far.
+    map: ValueMap,
+    /// The key of the entry that is being parsed.
+    key: String,
+    /// Keys whose values were collected into an array because of [ParseOptions::collect_duplicate_keys].
+    collected: hashbrown::HashSet<String>,
+}
+
+impl PartialObject {
+    fn new(key: String) -> Self {
+        Self::with_map(ValueMap::new(), key)
+    }
+
+    /// Create a [PartialObject] that adds its entries to `map`.
+    fn with_map(map: ValueMap, key: String) -> Self {
+        Self {
+            map,
+            key,
+            collected: hashbrown::HashSet::new(),
+        }
+    }
+}
+
+impl Value {
+    /// Parse a JSON [Value] from a string. This is the same as [Value::from_str], without
+    /// needing to import [FromStr].
+    ///
+    /// Note that `TryFrom<&str>` is not implemented for parsing, because [Value] implements
+    /// `From<&str>`, which creates a [Value::String]. So `"true".try_into()` gives the string
+    /// `"true"`, not the boolean.
+    pub fn parse(source: &str) -> ParseResult<Value> {
+        Value::parse_with(source, ParseOptions::default())
+    }
+
+    /// Parse a JSON [Value] from a string using the given [ParseOptions]. A leading UTF-8
+    /// byte order mark (`\u{FEFF}`) is skipped.
+    pub fn parse_with(source: &str, options: ParseOptions) -> ParseResult<Value> {
+        Parser::new(source, options).parse_document()
+    }
+
+    /// Parse a JSON [Value] like [Value::parse_with], and also return a JSON Pointer to every
+    /// key that appeared more than once in the same object, in the order they were found,
+    /// such as to warn about configuration that was silently overridden. A key is reported
+    /// once for each repeat.
+    pub fn parse_with_duplicate_report(source: &str, options: ParseOptions) -> ParseResult<(Value, Vec<String>)> {
+        let mut parser = Parser::new(source, options);
+        parser.duplicate_keys = Some(Vec::new());
+        let value = parser.parse_document()?;
+        Ok((value, parser.duplicate_keys.unwrap_or_default()))
+    }
+
+    /// Parse a JSON [Value] with [ParseOptions::lenient] enabled, and collect a [Warning] for
+    /// every use of non-standard syntax and every duplicate key, in the order they were
+    /// found, such as to lint a hand-written configuration file. The warnings found before a
+    /// parse error are also returned.
+    ///
+    /// `//` and `/* */` comments are skipped like whitespace, with a [Warning::Comment] for each.
+    pub fn parse_lenient(source: &str) -> (ParseResult<Value>, Vec<Warning>) {
+        let options = ParseOptions {
+            lenient: true,
+            ..Default::default()
+        };
+        let mut parser = Parser::new(source, options);
+        parser.warnings = Some(Vec::new());
+        let value = parser.parse_document();
+        (value, parser.warnings.unwrap_or_default())
+    }
+
+    /// Parse a JSON object from a string into an existing `map`, using
+    /// [ParseOptions::default]. The map is cleared first, and its allocation is reused, so
+    /// parsing many objects into the same map in a loop avoids allocating a new one each
+    /// time. If the source is not an object, this fails with [ParseError::InvalidCharacter].
+    /// On error, `map` is left empty, and it keeps its allocation.
+    pub fn parse_object_into(source: &str, map: &mut ValueMap) -> ParseResult<()> {
+        Parser::new(source, ParseOptions::default()).parse_object_into(map)
+    }
+
+    /// Parse a JSON [Value] from UTF-8 bytes, such as the contents of a file.
+    ///
+    /// The bytes are not validated up front: only the strings and numbers are checked as they
+    /// are read, because any other non-ASCII byte is already invalid JSON. Invalid UTF-8
+    /// fails with [ParseError::InvalidUtf8] at the index of the first invalid byte.
+    pub fn from_slice(source: &[u8]) -> ParseResult<Value> {
+        Value::from_slice_with(source, ParseOptions::default())
+    }
+
+    /// Parse a JSON [Value] from UTF-8 bytes using the given [ParseOptions].
+    /// See [Value::from_slice].
+    pub fn from_slice_with(source: &[u8], options: ParseOptions) -> ParseResult<Value> {
+        Parser::from_slice(source, options).parse_document()
+    }
+}
+
+/// Parses into an [Arena] with the primitives of a [Parser]. See [Arena::parse_with].
+#[cfg(feature = "arena")]
+struct ArenaParser<'s, 'a> {
+    parser: Parser<'s>,
+    arena: &'a Arena,
+    /// The elements parsed so far in the open arrays, innermost last.
+    values: Vec<arena::Value<'a>>,
+    /// The entries parsed so far in the open objects, innermost last.
+    entries: Vec<(&'a str, arena::Value<'a>)>,
+    /// For each key of the open objects, by the depth of its object: the index of its entry in
+    /// `entries`, and whether its values were collected by [ParseOptions::collect_duplicate_keys].
+    keys: hashbrown::HashMap<(usize, &'a str), (usize, bool)>,
+    /// A buffer that strings are unescaped into before they are copied into the arena.
+    unescaped: String,
+}
+
+/// An array or object that is still being parsed by [ArenaParser::parse_value]. `start` is
+/// the index of its first element in [ArenaParser::values] or [ArenaParser::entries].
+#[cfg(feature = "arena")]
+enum ArenaContainer<'a> {
+    Array { start: usize },
+    Object { start: usize, key: &'a str },
+}
+
+#[cfg(feature = "arena")]
+impl<'s, 'a> ArenaParser<'s, 'a> {
+    fn new(parser: Parser<'s>, arena: &'a Arena) -> Self {
+        Self {
+            parser,
+            arena,
+            values: Vec::new(),
+            entries: Vec::new(),
+            keys: hashbrown::HashMap::new(),
+            unescaped: String::new(),
+        }
+    }
+
+    /// Parse a string into the arena, passing it through [ParseOptions::key_normalizer] if
+    /// it is a key. Strings without escapes are copied straight from the source.
+    fn parse_string(&mut self, is_key: bool) -> ParseResult<&'a str> {
+        let raw = self.parser.parse_raw_string()?;
+        let normalizer = self.parser.options.key_normalizer.as_ref().filter(|_| is_key);
+        if normalizer.is_none() && !raw.contains('\\') {
+            return Ok(self.arena.alloc_str(raw));
+        }
+        self.unescaped.clear();
+        self.parser.unescape_into(raw, &mut self.unescaped)?;
+        Ok(match normalizer {
+            Some(KeyNormalizer(normalizer)) => self.arena.alloc_str(&normalizer(&self.unescaped)),
+            None => self.arena.alloc_str(&self.unescaped),
+        })
+    }
+
+    /// Parse the key and separator of the next entry in an object after `{` or `,`. See
+    /// [Parser::parse_entry_key].
+    fn parse_entry_key(&mut self) -> ParseResult<Option<&'a str>> {
+        if !self.parser.object_continues()? {
+            return Ok(None);
+        }
+        let key = self.parse_string(true)?;
+        self.parser.parse_key_separator()?;
+        Ok(Some(key))
+    }
+
+    /// Parse a JSON Value that isn't an array or object.
+    fn parse_scalar(&mut self) -> ParseResult<arena::Value<'a>> {
+        if self.parser.peek() == Some(b'"') {
+            return Ok(arena::Value::String(self.parse_string(false)?));
+        }
+        Ok(match self.parser.parse_scalar()? {
+            Value::Null => arena::Value::Null,
+            Value::Boolean(boolean) => arena::Value::Boolean(boolean),
+            Value::Number(Number::Float(float)) => arena::Value::Number(arena::Number::Float(float)),
+            Value::Number(Number::Int(int)) => arena::Value::Number(arena::Number::Int(int)),
+            Value::Number(Number::Big(text)) => arena::Value::Number(arena::Number::Big(self.arena.alloc_str(&text))),
+            // Strings are parsed above, and containers aren't scalars.
+            _ => unreachable!(),
+        })
+    }
+
+    /// Add an entry to the innermost open object, which starts at `start` in `entries` and
+    /// is at `depth`. Like [Parser::insert_entry], a duplicate key replaces the value of the
+    /// first entry with that key, or is collected with it.
+    fn insert_entry(&mut self, depth: usize, start: usize, key: &'a str, value: arena::Value<'a>) -> ParseResult<()> {
+        let options = &self.parser.options;
+        match self.keys.entry((depth, key)) {
+            hashbrown::hash_map::Entry::Occupied(mut entry) => {
+                let (index, collected) = entry.get_mut();
+                let existing = &mut self.entries[*index].1;
+                if !options.collect_duplicate_keys {
+                    *existing = value;
+                    return Ok(());
+                }
+                let mark = self.values.len();
+                match *existing {
+                    arena::Value::Array(array) if *collected => self.values.extend_from_slice(array),
+                    previous => self.values.push(previous),
+                }
+                self.values.push(value);
+                *existing = arena::Value::Array(self.arena.alloc_slice(&self.values[mark..]));
+                self.values.truncate(mark);
+                *collected = true;
+            }
+            hashbrown::hash_map::Entry::Vacant(entry) => {
+                entry.insert((self.entries.len(), false));
+                self.entries.push((key, value));
+                if options.max_object_entries.is_some_and(|max| self.entries.len() - start > max) {
+                    return Err(ParseError::LimitExceeded { index: self.parser.index });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Parse a JSON Value into the arena, with an explicit stack of open containers like
+    /// [Parser::parse_value].
+    fn parse_value(&mut self) -> ParseResult<arena::Value<'a>> {
+        let mut stack = Vec::new();
+        'value: loop {
+            let mut value = match self.parser.peek() {
+                Some(b'[') => {
+                    self.parser.open_container(stack.len())?;
+                    if self.parser.array_continues()? {
+                        stack.push(ArenaContainer::Array { start: self.values.len() });
+                        continue 'value;
+                    }
+                    arena::Value::Array(&[])
+                }
+                Some(b'{') => {
+                    self.parser.open_container(stack.len())?;
+                    match self.parse_entry_key()? {
+                        Some(key) => {
+                            stack.push(ArenaContainer::Object { start: self.entries.len(), key });
+                            continue 'value;
+                        }
+                        None => arena::Value::Object(&[]),
+                    }
+                }
+                _ => self.parse_scalar()?,
+            };
+            // Add the value to the innermost open container, closing containers as they end.
+            loop {
+                let depth = stack.len().wrapping_sub(1);
+                let closed = match stack.last_mut() {
+                    None => return Ok(value),
+                    Some(ArenaContainer::Array { .. }) => {
+                        self.values.push(value);
+                        self.parser.array_closes()?
+                    }
+                    Some(ArenaContainer::Object { start, key }) => {
+                        self.insert_entry(depth, *start, key, value)?;
+                        let closed = self.parser.object_closes()?;
+                        if !closed {
+                            *key = self.parse_string(true)?;
+                            self.parser.parse_key_separator()?;
+                        }
+                        closed
+                    }
+                };
+                if !closed {
+                    continue 'value;
+                }
+                value = match stack.pop() {
+                    Some(ArenaContainer::Array { start }) => {
+                        let array = self.arena.alloc_slice(&self.values[start..]);
+                        self.values.truncate(start);
+                        arena::Value::Array(array)
+                    }
+                    Some(ArenaContainer::Object { start, .. }) => {
+                        let entries = self.arena.alloc_slice(&self.entries[start..]);
+                        for (key, _) in entries {
+                            self.keys.remove(&(depth, *key));
+                        }
+                        self.entries.truncate(start);
+                        arena::Value::Object(entries)
+                    }
+                    None => unreachable!(),
+                };
+            }
+        }
+    }
+}
+
+#[cfg(feature = "arena")]
+impl Arena {
+    /// Parse a JSON [arena::Value] from a string, allocating its strings, arrays, and
+    /// objects in the arena. See [Arena::parse_with].
+    pub fn parse(&self, source: &str) -> ParseResult<arena::Value<'_>> {
+        self.parse_with(source, ParseOptions::default())
+    }
+
+    /// Parse a JSON [arena::Value] from a string using the given [ParseOptions], allocating
+    /// its strings, arrays, and objects in the arena. The value has the same contents as the
+    /// [Value] that [Value::parse_with] gives, and it is written the same way. Strings are
+    /// copied into the arena, so the value doesn't borrow `source`.
+    pub fn parse_with(&self, source: &str, options: ParseOptions) -> ParseResult<arena::Value<'_>> {
+        let mut parser = ArenaParser::new(Parser::new(source, options), self);
+        parser.parser.start_document()?;
+        let value = parser.parse_value()?;
+        parser.parser.end_document()?;
+        Ok(value)
+    }
 }
 
 impl FromStr for Value {
     type Err = ParseError;
     /// Parse a JSON [Value] from a string.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut parser = Parser::new(s);
-        parser.eat_whitespace();
-        let res = parser.parse_value()?;
-        parser.eat_whitespace();
-        if !parser.is_eof() {
-            Err(ParseError::InvalidCharacter(parser.index))
-        } else {
-            Ok(res)
+        Value::parse(s)
+    }
+}
+
+/// Parses a sequence of concatenated JSON values from input that arrives in chunks,
+/// such as from an [std::io::Read] feed.
+///
+/// Input is appended to an internal buffer with [StreamParser::feed], and values are taken
+/// from the front of it with [StreamParser::next_value]. The buffer's allocation is reused
+/// as values are consumed. A value that is cut off is kept partly parsed, and parsing
+/// continues where it stopped once more input is fed, so the input isn't parsed again.
+#[derive(Debug, Default, Clone)]
+pub struct StreamParser {
+    /// Input that has been fed, starting with the input that was already parsed.
+    buffer: String,
+    /// The index in `buffer` where the next value (or the whitespace before it) starts.
+    start: usize,
+    /// The containers of the cut off value that are still open.
+    stack: Vec<Container>,
+    /// Where to continue parsing the cut off value, relative to `start`, if there is one.
+    resume: Option<Checkpoint>,
+    /// The length of `buffer` when the cut off value was last parsed, if it was cut off in
+    /// a string. The string can't be finished (or fail) until a `"` or a line break is fed.
+    string_scanned: Option<usize>,
+    options: ParseOptions,
+}
+
+impl StreamParser {
+    /// Create a new [StreamParser] with the default [ParseOptions].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new [StreamParser] with the given [ParseOptions].
+    pub fn with_options(options: ParseOptions) -> Self {
+        Self {
+            options,
+            ..Self::default()
+        }
+    }
+
+    /// Append more input to the stream.
+    pub fn feed(&mut self, more: &str) {
+        if self.start > 0 {
+            // Only the input after `start` is needed, and the checkpoint is relative to it.
+            self.buffer.drain(..self.start);
+            self.string_scanned = self.string_scanned.map(|scanned| scanned - self.start);
+            self.start = 0;
         }
+        self.buffer.push_str(more);
+    }
+
+    /// Parse the next value from the stream. Returns `None` if more input is needed to
+    /// complete the next value. A number at the very end of the input is considered
+    /// incomplete because more digits may follow; use [StreamParser::finish] once the
+    /// input has ended.
+    ///
+    /// After an error, the remaining input is discarded.
+    pub fn next_value(&mut self) -> Option<ParseResult<Value>> {
+        self.parse_next(false)
     }
-}
\ No newline at end of file
+
+    /// Parse the next value from the stream, treating the end of the input as the end of the stream.
+    pub fn finish(&mut self) -> Option<ParseResult<Value>> {
+        self.parse_next(true)
+    }
+
+    fn parse_next(&mut self, at_end: bool) -> Option<ParseResult<Value>> {
+        if let Some(scanned) = self.string_scanned.filter(|_| !at_end && self.options.max_string_length.is_none()) {
+            if !self.buffer.as_bytes()[scanned..].iter().any(|byte| matches!(byte, b'"' | b'\n' | b'\r')) {
+                return None;
+            }
+        }
+        let mut parser = Parser::new(&self.buffer[self.start..], self.options.clone());
+        parser.partial = !at_end;
+        let result = match self.resume.take() {
+            Some(checkpoint) => {
+                parser.index = checkpoint.index;
+                parser.parse_nested(&mut self.stack, checkpoint.after_value)
+            }
+            None => match parser.eat_whitespace() {
+                // A comment is cut off, so start from the whitespace before it again.
+                Err(ParseError::UnexpectedEOF) if !at_end => return None,
+                Err(err) => Err(err),
+                Ok(()) if parser.is_eof() => {
+                    self.buffer.clear();
+                    self.start = 0;
+                    return None;
+                }
+                Ok(()) => parser.parse_nested(&mut self.stack, false),
+            },
+        };
+        self.string_scanned = None;
+        match result {
+            Err(err @ (ParseError::UnexpectedEOF | ParseError::UnexpectedEOFWhileParsingString(_))) if !at_end => {
+                self.resume = Some(parser.checkpoint);
+                if matches!(err, ParseError::UnexpectedEOFWhileParsingString(_)) {
+                    self.string_scanned = Some(self.buffer.len());
+                }
+                None
+            }
+            Ok(value) => {
+                self.start += parser.index;
+                Some(Ok(value))
+            }
+            Err(err) => {
+                self.buffer.clear();
+                self.start = 0;
+                self.stack.clear();
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lenient() -> ParseOptions {
+        ParseOptions {
+            lenient: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn lenient_separator_test() -> ParseResult<()> {
+        let value = Value::parse_with(r#"{"a" = 1, "b" => 2, "c": 3}"#, lenient())?;
+        assert!(matches!(value["a"], Value::Number(Number::Int(1))));
+        assert!(matches!(value["b"], Value::Number(Number::Int(2))));
+        assert!(matches!(value["c"], Value::Number(Number::Int(3))));
+        assert!(matches!(Value::from_str(r#"{"a" = 1}"#), Err(ParseError::InvalidCharacter(5))));
+        assert!(matches!(Value::from_str(r#"{"a" => 1}"#), Err(ParseError::InvalidCharacter(5))));
+        Ok(())
+    }
+
+    #[test]
+    fn stream_parser_test() -> ParseResult<()> {
+        let mut stream = StreamParser::new();
+        stream.feed(r#"{"a": [1, "t"#);
+        assert!(stream.next_value().is_none());
+        stream.feed(r#"wo"]} tr"#);
+        assert_eq!(stream.next_value().transpose()?, Some(Value::from_str(r#"{"a": [1, "two"]}"#)?));
+        assert!(stream.next_value().is_none());
+        stream.feed("ue 12");
+        assert_eq!(stream.next_value().transpose()?, Some(Value::Boolean(true)));
+        assert!(stream.next_value().is_none());
+        stream.feed("34");
+        assert!(stream.next_value().is_none());
+        assert_eq!(stream.finish().transpose()?, Some(Value::from(1234i64)));
+        assert!(stream.next_value().is_none());
+
+        stream.feed("[1, }");
+        assert!(matches!(stream.next_value(), Some(Err(ParseError::InvalidCharacter(4)))));
+        assert!(stream.next_value().is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn stream_parser_chunks_test() -> ParseResult<()> {
+        let object = format!(
+            r#"{{"items": [{}], "name": "{}", "flags": [true, false, null]}}"#,
+            (0..1000).map(|i| format!(r#"{{"id": {i}, "tag": "t\"{i}"}}"#)).collect::<Vec<_>>().join(", "),
+            "long ".repeat(1000),
+        );
+        let source = format!("{object} [-1.5e3, 2] ");
+        let mut stream = StreamParser::new();
+        let mut values = Vec::new();
+        for chunk in source.as_bytes().chunks(3) {
+            stream.feed(std::str::from_utf8(chunk).unwrap());
+            values.extend(std::iter::from_fn(|| stream.next_value()));
+        }
+        values.extend(stream.finish());
+        assert_eq!(values.len(), 2);
+        assert_eq!(values.remove(0)?, Value::from_str(&object)?);
+        assert_eq!(values.remove(0)?, Value::from_str("[-1.5e3, 2]")?);
+
+        let mut stream = StreamParser::with_options(lenient());
+        for chunk in r#"{"a" => [0x10, tr"#.as_bytes().chunks(1) {
+            stream.feed(std::str::from_utf8(chunk).unwrap());
+            assert!(stream.next_value().is_none());
+        }
+        stream.feed("ue]}");
+        assert_eq!(stream.next_value().transpose()?, Some(Value::from_str(r#"{"a": [16, true]}"#)?));
+        assert!(stream.finish().is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn max_string_length_test() -> ParseResult<()> {
+        let options = ParseOptions {
+            max_string_length: Some(5),
+            ..Default::default()
+        };
+        let value = Value::parse_with(r#"{"abcde": "12345"}"#, options.clone())?;
+        assert!(matches!(&value["abcde"], Value::String(s) if s == "12345"));
+        let long = format!(r#"["{}"]"#, "x".repeat(1 << 20));
+        assert!(matches!(Value::parse_with(&long, options.clone()), Err(ParseError::StringTooLong { index: 7 })));
+        assert!(matches!(Value::parse_with(r#"{"abcdef": 1}"#, options), Err(ParseError::StringTooLong { index: 7 })));
+
+        // An escape counts as its raw bytes, including when it ends the string.
+        let options = ParseOptions {
+            max_string_length: Some(3),
+            ..Default::default()
+        };
+        assert_eq!(Value::parse_with(r#""a\n""#, options.clone())?, "a\n");
+        assert!(matches!(Value::parse_with(r#""ab\n""#, options.clone()), Err(ParseError::StringTooLong { index: 4 })));
+        assert!(matches!(Value::parse_with(r#""abc\n""#, options.clone()), Err(ParseError::StringTooLong { index: 4 })));
+        assert!(matches!(Value::parse_with(r#""\u00e9""#, options), Err(ParseError::StringTooLong { .. })));
+        Ok(())
+    }
+
+    #[test]
+    fn deep_nesting_test() -> ParseResult<()> {
+        fn nested(depth: usize) -> String {
+            format!("{}{}", "[".repeat(depth), "]".repeat(depth))
+        }
+        let value = Value::from_str(&nested(DEFAULT_MAX_DEPTH))?;
+        assert_eq!(value.pointer(&"/0".repeat(DEFAULT_MAX_DEPTH - 1)), Some(&Value::Array(Vec::new())));
+        drop(value);
+
+        // Documents that are too deep to drop safely fail cleanly, including when the
+        // parse fails after the deep part.
+        const DEPTH: usize = 100_000;
+        let source = nested(DEPTH);
+        assert!(matches!(Value::from_str(&source), Err(ParseError::DepthLimitExceeded { index: DEFAULT_MAX_DEPTH })));
+        assert!(matches!(Value::from_str(&format!("{source} x")), Err(ParseError::DepthLimitExceeded { .. })));
+        let unclosed = format!("{} x]", "[".repeat(DEPTH));
+        assert!(matches!(Value::from_str(&unclosed), Err(ParseError::DepthLimitExceeded { .. })));
+
+        // The limit can be lifted, since the parser doesn't recurse.
+        let unlimited = ParseOptions {
+            max_depth: None,
+            ..Default::default()
+        };
+        let value = Value::parse_with(&nested(1000), unlimited.clone())?;
+        assert_eq!(value.pointer(&"/0".repeat(999)), Some(&Value::Array(Vec::new())));
+        drop(value);
+        assert!(matches!(Value::parse_with(&unclosed, unlimited.clone()), Err(ParseError::InvalidCharacter(i)) if i == DEPTH + 1));
+        // Dropping the value recurses, so it needs a bigger stack than the test thread's.
+        std::thread::Builder::new().stack_size(1 << 28).spawn(move || {
+            let value = Value::parse_with(&nested(DEPTH), unlimited)?;
+            assert_eq!(value.pointer(&"/0".repeat(DEPTH - 1)), Some(&Value::Array(Vec::new())));
+            ParseResult::Ok(())
+        }).unwrap().join().unwrap()?;
+
+        let options = ParseOptions {
+            max_depth: Some(3),
+            ..Default::default()
+        };
+        Value::parse_with(r#"[{"a": [1]}, {}, []]"#, options.clone())?;
+        assert!(matches!(
+            Value::parse_with(r#"[{"a": [[]]}]"#, options.clone()),
+            Err(ParseError::DepthLimitExceeded { index: 8 }),
+        ));
+        assert!(matches!(Value::parse_with(&source, options), Err(ParseError::DepthLimitExceeded { index: 3 })));
+        Ok(())
+    }
+
+    #[test]
+    fn container_syntax_test() -> ParseResult<()> {
+        let value = Value::from_str(r#" { "a" : [ 1 , [ ] , { } , ] , "b" : { "c" : null , } , } "#)?;
+        assert_eq!(value.to_string().len(), r#"{"a":[1,[],{}],"b":{"c":null}}"#.len());
+        assert!(matches!(Value::from_str("[1 2]"), Err(ParseError::InvalidCharacter(3))));
+        assert!(matches!(Value::from_str(r#"{"a": 1 "b": 2}"#), Err(ParseError::InvalidCharacter(8))));
+        assert!(matches!(Value::from_str("{1: 2}"), Err(ParseError::InvalidCharacter(1))));
+        assert!(matches!(Value::from_str("[1, ?]"), Err(ParseError::InvalidCharacter(4))));
+        assert!(matches!(Value::from_str("[1,"), Err(ParseError::UnexpectedEOF)));
+        assert!(matches!(Value::from_str(r#"{"a":"#), Err(ParseError::UnexpectedEOF)));
+        Ok(())
+    }
+
+    #[test]
+    fn parse_test() -> ParseResult<()> {
+        assert_eq!(Value::parse("true")?, Value::Boolean(true));
+        assert_eq!(Value::parse(&String::from(r#" {"a": [1]} "#))?, Value::from_str(r#"{"a":[1]}"#)?);
+        assert!(Value::parse("tru").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn collect_duplicate_keys_test() -> ParseResult<()> {
+        let options = ParseOptions {
+            collect_duplicate_keys: true,
+            ..Default::default()
+        };
+        let source = r#"{"x": 1, "x": 2, "y": [3], "y": 4, "x": 5, "z": [6], "o": {"x": 7, "x": 8}}"#;
+        let value = Value::parse_with(source, options)?;
+        let expected = Value::from_str(r#"{"x": [1, 2, 5], "y": [[3], 4], "z": [6], "o": {"x": [7, 8]}}"#)?;
+        assert_eq!(value, expected);
+        assert_eq!(Value::from_str(r#"{"x": 1, "x": 2}"#)?, Value::from_str(r#"{"x": 2}"#)?);
+        Ok(())
+    }
+
+    #[test]
+    fn preserve_big_integers_test() -> ParseResult<()> {
+        let options = ParseOptions {
+            preserve_big_integers: true,
+            ..Default::default()
+        };
+        let source = "[9223372036854775807,-9223372036854775808,9223372036854775808,-123456789012345678901234567890,1.5]";
+        let value = Value::parse_with(source, options.clone())?;
+        assert!(matches!(value[0], Value::Number(Number::Int(i64::MAX))));
+        assert!(matches!(value[1], Value::Number(Number::Int(i64::MIN))));
+        assert!(matches!(&value[2], Value::Number(Number::Big(text)) if text == "9223372036854775808"));
+        assert!(matches!(&value[3], Value::Number(Number::Big(text)) if text == "-123456789012345678901234567890"));
+        assert!(matches!(value[4], Value::Number(Number::Float(_))));
+        assert_eq!(value.to_string(), source);
+        assert!(matches!(&Value::parse_with("+99999999999999999999", options.clone())?, Value::Number(Number::Big(text)) if text == "99999999999999999999"));
+        assert!(matches!(&Value::parse_with("-00099999999999999999999", options)?, Value::Number(Number::Big(text)) if text == "-99999999999999999999"));
+        assert!(matches!(Value::from_str("9223372036854775808"), Err(ParseError::ParseIntError(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn line_column_test() {
+        let source = "{\r\n  \"a\": 1,\r\n  \"b\": x\r\n}";
+        let err = Value::from_str(source).unwrap_err();
+        assert!(matches!(err, ParseError::InvalidCharacter(_)));
+        assert_eq!(err.line_column(source), Some((3, 8)));
+        let unix = source.replace("\r\n", "\n");
+        assert_eq!(Value::from_str(&unix).unwrap_err().line_column(&unix), Some((3, 8)));
+
+        assert_eq!(line_column(source, 0), (1, 1));
+        assert_eq!(line_column(source, 3), (2, 1));
+        assert_eq!(line_column("a\rb\n\nc", 5), (4, 1));
+        assert_eq!(line_column("\"é\" x", 5), (1, 5));
+        assert!(ParseError::UnexpectedEOF.line_column(source).is_none());
+    }
+
+    #[test]
+    fn radix_integer_test() -> ParseResult<()> {
+        let value = Value::parse_with("[0xFF, 0o17, 0b1010, -0x10, +0Xff, 0x7fffffffffffffff, 0]", lenient())?;
+        let expected = [255, 15, 10, -16, 255, i64::MAX, 0];
+        for (value, expected) in value.as_array().unwrap().iter().zip(expected) {
+            assert!(matches!(value, &Value::Number(Number::Int(int)) if int == expected), "{value}");
+        }
+        assert!(matches!(Value::parse_with("{\"a\": 0xff }", lenient())?["a"], Value::Number(Number::Int(255))));
+        assert!(matches!(Value::parse_with("0x", lenient()), Err(ParseError::InvalidCharacter(2))));
+        assert!(matches!(Value::parse_with("0b102", lenient()), Err(ParseError::InvalidCharacter(4))));
+        assert!(matches!(Value::parse_with("0x1.5", lenient()), Err(ParseError::InvalidCharacter(3))));
+        assert!(matches!(Value::parse_with("0x10000000000000000", lenient()), Err(ParseError::ParseIntError(_))));
+        assert!(matches!(Value::from_str("0xFF"), Err(ParseError::InvalidCharacter(1))));
+        assert!(matches!(Value::from_str("0b1010"), Err(ParseError::InvalidCharacter(1))));
+        Ok(())
+    }
+
+    #[test]
+    fn max_object_entries_test() -> ParseResult<()> {
+        let options = ParseOptions {
+            max_object_entries: Some(2),
+            ..Default::default()
+        };
+        Value::parse_with(r#"{"a": 1, "b": {"c": 2, "d": 3}}"#, options.clone())?;
+        // Repeated keys don't add entries.
+        Value::parse_with(r#"{"a": 1, "b": 2, "a": 3}"#, options.clone())?;
+        assert!(matches!(
+            Value::parse_with(r#"{"a": 1, "b": 2, "c": 3}"#, options.clone()),
+            Err(ParseError::LimitExceeded { index: 23 }),
+        ));
+        assert!(matches!(
+            Value::parse_with(r#"[{"a": [{"b": 1, "c": 2, "d": 3}]}]"#, options),
+            Err(ParseError::LimitExceeded { .. }),
+        ));
+        Value::from_str(r#"{"a": 1, "b": 2, "c": 3}"#)?;
+        Ok(())
+    }
+
+    #[test]
+    fn from_slice_test() -> ParseResult<()> {
+        let source = r#"{"name": "Frédéric", "tags": ["🦀", 1.5, -2]}"#;
+        assert_eq!(Value::from_slice(source.as_bytes())?, Value::from_str(source)?);
+        assert_eq!(Value::from_slice("\u{feff}[1]".as_bytes())?, Value::from_str("[1]")?);
+
+        let mut invalid = br#"{"a": "ok", "b": "bad "#.to_vec();
+        let index = invalid.len();
+        invalid.extend_from_slice(b"\xff\xfe\"}");
+        assert!(matches!(Value::from_slice(&invalid), Err(ParseError::InvalidUtf8 { index: i }) if i == index));
+        // A truncated multibyte character.
+        assert!(matches!(Value::from_slice(b"[\"ab\xe2\x82\"]"), Err(ParseError::InvalidUtf8 { index: 4 })));
+        assert!(matches!(Value::from_slice(b"[1, \xff]"), Err(ParseError::InvalidCharacter(4))));
+        Ok(())
+    }
+
+    #[test]
+    fn key_normalizer_test() -> ParseResult<()> {
+        let source = r#"{"Name": "Fred", "AGE": 30, "nested": {"Key": 1}, "name": "George"}"#;
+        let mut options = ParseOptions {
+            key_normalizer: Some(KeyNormalizer::new(str::to_lowercase)),
+            ..Default::default()
+        };
+        let value = Value::parse_with(source, options.clone())?;
+        assert_eq!(value.len(), 3);
+        assert!(value["name"] == "George");
+        assert!(value["age"] == 30);
+        assert!(value["nested"]["key"] == 1);
+
+        options.collect_duplicate_keys = true;
+        let value = Value::parse_with(source, options)?;
+        assert_eq!(value["name"], Value::parse(r#"["Fred", "George"]"#)?);
+
+        // Closures can capture state, and clones of a normalizer are equal.
+        let prefix = String::from("user.");
+        let options = ParseOptions {
+            key_normalizer: Some(KeyNormalizer::new(move |key| format!("{prefix}{key}"))),
+            ..Default::default()
+        };
+        assert_eq!(options, options.clone());
+        assert_ne!(options, ParseOptions::default());
+        let value = Value::parse_with(r#"{"id": 1}"#, options)?;
+        assert!(value["user.id"] == 1);
+        Ok(())
+    }
+
+    #[test]
+    fn duplicate_report_test() -> ParseResult<()> {
+        let source = r#"
+            {
+                "name": "first",
+                "servers": [{"port": 1}, {"port": 2, "host": "a", "port": 3}],
+                "a/b": {"x": 1, "x": 2, "x": 3},
+                "name": "last"
+            }
+        "#;
+        let (value, duplicates) = Value::parse_with_duplicate_report(source, ParseOptions::default())?;
+        assert_eq!(duplicates, ["/servers/1/port", "/a~1b/x", "/a~1b/x", "/name"]);
+        assert!(value["name"] == "last");
+        assert!(value["servers"][1]["port"] == 3);
+        assert_eq!(value, Value::from_str(source)?);
+
+        let (_, duplicates) = Value::parse_with_duplicate_report(r#"[{"a": 1}, {"a": 2}]"#, ParseOptions::default())?;
+        assert!(duplicates.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn max_number_digits_test() -> ParseResult<()> {
+        let options = ParseOptions {
+            max_number_digits: Some(20),
+            ..Default::default()
+        };
+        Value::parse_with("[1234567890123456789, -1.2345678901234567890, 1.5e-300]", options.clone())?;
+        let huge = format!("[1, {}]", "9".repeat(1_000_000));
+        assert!(matches!(Value::parse_with(&huge, options.clone()), Err(ParseError::NumberTooLong { index: 24 })));
+        assert!(matches!(Value::parse_with("1.00000000000000000000", options.clone()), Err(ParseError::NumberTooLong { index: 21 })));
+        let lenient = ParseOptions {
+            lenient: true,
+            ..options
+        };
+        Value::parse_with("0xffffffffffffffff", lenient.clone()).unwrap_err();
+        assert!(matches!(Value::parse_with(&format!("0x{}", "f".repeat(21)), lenient), Err(ParseError::NumberTooLong { index: 22 })));
+        Ok(())
+    }
+
+    #[test]
+    fn parse_object_into_test() -> ParseResult<()> {
+        let mut map = ValueMap::with_capacity(16);
+        Value::parse_object_into(r#"{"a": 1, "b": [2, {"c": 3}]}"#, &mut map)?;
+        assert_eq!(Value::Object(map.clone()), Value::parse(r#"{"a": 1, "b": [2, {"c": 3}]}"#)?);
+        let capacity = map.capacity();
+
+        Value::parse_object_into(r#" {"d": null} "#, &mut map)?;
+        assert_eq!(Value::Object(map.clone()), Value::parse(r#"{"d": null}"#)?);
+        assert_eq!(map.capacity(), capacity);
+
+        Value::parse_object_into("{}", &mut map)?;
+        assert!(map.is_empty());
+        assert!(matches!(Value::parse_object_into("", &mut map), Err(ParseError::UnexpectedEOF)));
+
+        // The map is left empty on every error, and keeps its allocation.
+        let errors = [
+            ("[1]", 0),
+            (r#"{"b": 2} x"#, 9),
+            (r#"{"a": 1} 2"#, 9),
+            (r#"{"a": 1, "b": [2, {"c": x}]}"#, 24),
+        ];
+        for (source, index) in errors {
+            Value::parse_object_into(r#"{"old": 1}"#, &mut map)?;
+            let result = Value::parse_object_into(source, &mut map);
+            assert!(matches!(result, Err(ParseError::InvalidCharacter(i)) if i == index), "{source}");
+            assert!(map.is_empty(), "{source}");
+            assert_eq!(map.capacity(), capacity, "{source}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn parse_lenient_test() -> ParseResult<()> {
+        let (value, warnings) = Value::parse_lenient(r#"{"a": [1, 2,], "b": {"c": 3,},}"#);
+        assert_eq!(value?, Value::parse(r#"{"a": [1, 2], "b": {"c": 3}}"#)?);
+        assert_eq!(warnings, [
+            Warning::TrailingComma { index: 11 },
+            Warning::TrailingComma { index: 27 },
+            Warning::TrailingComma { index: 29 },
+        ]);
+
+        let (value, warnings) = Value::parse_lenient(r#"{"a" = 0x10, "b" => +2, "a": -0b1}"#);
+        assert_eq!(value?, Value::parse(r#"{"a": -1, "b": 2}"#)?);
+        assert_eq!(warnings, [
+            Warning::KeySeparator { index: 5 },
+            Warning::RadixInteger { index: 7 },
+            Warning::KeySeparator { index: 17 },
+            Warning::PlusSign { index: 20 },
+            Warning::RadixInteger { index: 29 },
+            Warning::DuplicateKey { pointer: "/a".to_owned() },
+        ]);
+
+        let (value, warnings) = Value::parse_lenient("[1, 2, 3]");
+        assert_eq!(value?, Value::parse("[1, 2, 3]")?);
+        assert!(warnings.is_empty());
+
+        // Warnings before an error are kept.
+        let (value, warnings) = Value::parse_lenient("[+1, x]");
+        assert!(matches!(value, Err(ParseError::InvalidCharacter(5))));
+        assert_eq!(warnings, [Warning::PlusSign { index: 1 }]);
+        Ok(())
+    }
+
+    #[test]
+    fn lenient_comments_test() -> ParseResult<()> {
+        let source = "// config\n{\"a\": 1, /* two */ \"b\" /**/: [2// end\r\n, 0x3/*
+            */], \"c\": \"// not a comment\"} // done";
+        let (value, warnings) = Value::parse_lenient(source);
+        assert_eq!(value?, Value::parse(r#"{"a": 1, "b": [2, 3], "c": "// not a comment"}"#)?);
+        assert_eq!(warnings, [
+            Warning::Comment { index: 0 },
+            Warning::Comment { index: 19 },
+            Warning::Comment { index: 33 },
+            Warning::Comment { index: 41 },
+            Warning::RadixInteger { index: 51 },
+            Warning::Comment { index: 54 },
+            Warning::Comment { index: 99 },
+        ]);
+
+        assert!(matches!(Value::parse_lenient("[1 /* open").0, Err(ParseError::UnexpectedEOF)));
+        assert!(matches!(Value::parse_lenient("[1 /*/]").0, Err(ParseError::UnexpectedEOF)));
+        assert!(matches!(Value::parse_lenient("[1 / 2]").0, Err(ParseError::InvalidCharacter(3))));
+        assert!(matches!(Value::parse_lenient("1 /").0, Err(ParseError::InvalidCharacter(2))));
+        // Comments are still an error without `lenient`.
+        assert!(matches!(Value::parse("[1, /* two */ 2]"), Err(ParseError::InvalidCharacter(4))));
+        assert!(matches!(Value::parse("1// one"), Err(ParseError::InvalidCharacter(1))));
+
+        // A comment can be cut off in a stream.
+        let mut stream = StreamParser::with_options(lenient());
+        let mut values = Vec::new();
+        for chunk in "/* one */ 1 // a\n [2, /* three */ 3] // end".as_bytes().chunks(2) {
+            stream.feed(std::str::from_utf8(chunk).unwrap());
+            values.extend(std::iter::from_fn(|| stream.next_value()));
+        }
+        values.extend(stream.finish());
+        assert_eq!(values.into_iter().collect::<ParseResult<Vec<_>>>()?, [Value::from(1i64), Value::parse("[2, 3]")?]);
+        stream.feed("/* open");
+        assert!(stream.next_value().is_none());
+        assert!(matches!(stream.finish(), Some(Err(ParseError::UnexpectedEOF))));
+        Ok(())
+    }
+
+    #[test]
+    fn strict_escapes_test() -> ParseResult<()> {
+        let strict = ParseOptions {
+            strict_escapes: true,
+            ..Default::default()
+        };
+        let source = r#"["a\/b", "\"\\\b\f\n\r\t\u00e9"]"#;
+        assert_eq!(Value::parse(source)?, Value::parse_with(source, strict.clone())?);
+        assert_eq!(Value::parse_with(source, strict.clone())?[0], "a/b");
+        assert_eq!(Value::parse(r#""\x""#)?, "x");
+        assert!(matches!(Value::parse_with(r#"[1, "ab\x"]"#, strict.clone()), Err(ParseError::InvalidEscapeSequence { index: 7 })));
+        assert!(matches!(Value::parse_with(r#"{"\<": 1}"#, strict.clone()), Err(ParseError::InvalidEscapeSequence { index: 2 })));
+        assert!(matches!(Value::parse_with(r#"{"a": 1, "\<": 1}"#, strict), Err(ParseError::InvalidEscapeSequence { index: 10 })));
+        assert!(matches!(Value::parse(r#" "\ud800""#), Err(ParseError::InvalidEscapeSequence { index: 2 })));
+        Ok(())
+    }
+
+    #[test]
+    fn disallow_surrounding_whitespace_test() -> ParseResult<()> {
+        let framed = ParseOptions {
+            disallow_surrounding_whitespace: true,
+            ..Default::default()
+        };
+        assert_eq!(Value::parse("  {}  ")?, Value::parse("{}")?);
+        assert_eq!(Value::parse_with(r#"{ "a" : [1, 2] }"#, framed.clone())?, Value::parse(r#"{"a": [1, 2]}"#)?);
+        assert_eq!(Value::parse_with("\u{feff}1", framed.clone())?, 1i64);
+        assert!(matches!(Value::parse_with("  {}  ", framed.clone()), Err(ParseError::InvalidCharacter(0))));
+        assert!(matches!(Value::parse_with("{}\n", framed.clone()), Err(ParseError::InvalidCharacter(2))));
+        assert!(matches!(Value::parse_with("1 ", framed.clone()), Err(ParseError::InvalidCharacter(1))));
+        assert!(matches!(Value::parse_with("\u{feff} 1", framed), Err(ParseError::InvalidCharacter(3))));
+        Ok(())
+    }
+}
@@ -0,0 +1,336 @@
+// JSON Pointer (RFC 6901) support.
+use std::borrow::Cow;
+
+use crate::{Value, ValueMap};
+
+/// Unescape a single reference token (`~1` becomes `/` and `~0` becomes `~`).
+fn unescape_token(token: &str) -> Cow<'_, str> {
+    if token.contains('~') {
+        Cow::Owned(token.replace("~1", "/").replace("~0", "~"))
+    } else {
+        Cow::Borrowed(token)
+    }
+}
+
+/// Escape a key for use as a reference token (`~` becomes `~0` and `/` becomes `~1`).
+pub fn escape_token(key: &str) -> Cow<'_, str> {
+    if key.contains(['~', '/']) {
+        Cow::Owned(key.replace('~', "~0").replace('/', "~1"))
+    } else {
+        Cow::Borrowed(key)
+    }
+}
+
+/// Split a JSON Pointer into its unescaped reference tokens.
+///
+/// Returns `None` if `pointer` is not empty and does not start with `/`.
+/// The empty pointer (`""`) refers to the whole document and has no tokens.
+pub fn tokens(pointer: &str) -> Option<impl Iterator<Item = Cow<'_, str>>> {
+    let rest = if pointer.is_empty() {
+        None
+    } else {
+        Some(pointer.strip_prefix('/')?)
+    };
+    Some(rest.into_iter().flat_map(|rest| rest.split('/')).map(unescape_token))
+}
+
+/// Parse a reference token as an array index. Leading zeros are not allowed.
+pub fn index_token(token: &str) -> Option<usize> {
+    if token.is_empty()
+    || (token.len() > 1 && token.starts_with('0'))
+    || !token.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    token.parse().ok()
+}
+
+/// Checks if `token` refers to the child at `index` of an array.
+fn is_index(token: &str, index: usize) -> bool {
+    index_token(token) == Some(index)
+}
+
+impl Value {
+    /// Get an immutable reference to the child [Value] referred to by a reference token.
+    fn child(&self, token: &str) -> Option<&Value> {
+        match self {
+            Value::Object(object) => object.get(token),
+            Value::Array(array) => array.get(index_token(token)?),
+            _ => None,
+        }
+    }
+
+    /// Get a mutable reference to the child [Value] referred to by a reference token.
+    fn child_mut(&mut self, token: &str) -> Option<&mut Value> {
+        match self {
+            Value::Object(object) => object.get_mut(token),
+            Value::Array(array) => array.get_mut(index_token(token)?),
+            _ => None,
+        }
+    }
+
+    /// Get a mutable reference to the child [Value] referred to by a reference token, creating
+    /// it if it doesn't exist. [Value::Null] becomes an empty [Value::Object] first. For arrays,
+    /// the index one past the end (or `-`) appends a [Value::Null].
+    fn child_or_insert(&mut self, token: &str) -> Option<&mut Value> {
+        if let Value::Null = self {
+            *self = Value::Object(ValueMap::new());
+        }
+        match self {
+            Value::Object(object) => Some(object.entry(token.to_owned()).or_insert(Value::Null)),
+            Value::Array(array) => {
+                let index = if token == "-" { array.len() } else { index_token(token)? };
+                if index == array.len() {
+                    array.push(Value::Null);
+                }
+                array.get_mut(index)
+            }
+            _ => None,
+        }
+    }
+
+    /// Get an immutable reference to the [Value] referred to by a JSON Pointer (e.g. `"/users/0/name"`).
+    pub fn pointer(&self, pointer: &str) -> Option<&Value> {
+        tokens(pointer)?.try_fold(self, |value, token| value.child(&token))
+    }
+
+    /// Clone only the subtree referred to by a JSON Pointer, leaving the rest of the document
+    /// uncloned.
+    pub fn clone_at(&self, pointer: &str) -> Option<Value> {
+        self.pointer(pointer).cloned()
+    }
+
+    /// Get a mutable reference to the [Value] referred to by a JSON Pointer (e.g. `"/users/0/name"`).
+    pub fn pointer_mut(&mut self, pointer: &str) -> Option<&mut Value> {
+        tokens(pointer)?.try_fold(self, |value, token| value.child_mut(&token))
+    }
+
+    /// Get a mutable reference to the [Value] referred to by a JSON Pointer, creating any
+    /// missing [Value]s along the way. Missing values and [Value::Null]s along the path become
+    /// [Value::Object]s, and the final value is created as [Value::Null].
+    ///
+    /// Returns `None` if the pointer is invalid, passes through a value that is not
+    /// [Value::Null], [Value::Array], or [Value::Object], or uses an array index past the end.
+    pub fn pointer_or_insert(&mut self, pointer: &str) -> Option<&mut Value> {
+        tokens(pointer)?.try_fold(self, |value, token| value.child_or_insert(&token))
+    }
+
+    /// Checks if a JSON Pointer resolves to a [Value] in this document.
+    pub fn path_exists(&self, pointer: &str) -> bool {
+        self.pointer(pointer).is_some()
+    }
+
+    /// Prune the document so that it only contains the data reachable by the given JSON Pointers.
+    /// Ancestors of the retained values are kept, and everything else is removed. Retained
+    /// elements of arrays are shifted down to fill the gaps left by removed elements.
+    ///
+    /// Invalid pointers are ignored. If nothing is reachable, containers are left empty and
+    /// other values are replaced with [Value::Null].
+    pub fn retain_paths(&mut self, paths: &[&str]) {
+        let paths: Vec<Vec<Cow<'_, str>>> = paths.iter()
+            .filter_map(|path| Some(tokens(path)?.collect()))
+            .collect();
+        let paths: Vec<&[Cow<'_, str>]> = paths.iter().map(Vec::as_slice).collect();
+        if !retain_tokens(self, &paths) && !matches!(self, Value::Array(_) | Value::Object(_)) {
+            *self = Value::Null;
+        }
+    }
+
+    /// Compare this [Value] with `other` structurally, ignoring the values at the JSON Pointers
+    /// in `ignore` (such as timestamps or request IDs). An ignored value may be missing from
+    /// either side. Invalid pointers are ignored.
+    pub fn eq_ignoring(&self, other: &Value, ignore: &[&str]) -> bool {
+        let paths: Vec<Vec<Cow<'_, str>>> = ignore.iter()
+            .filter_map(|path| Some(tokens(path)?.collect()))
+            .collect();
+        let paths: Vec<&[Cow<'_, str>]> = paths.iter().map(Vec::as_slice).collect();
+        eq_ignoring_tokens(self, other, &paths)
+    }
+
+    /// Replace the [Value] at each JSON Pointer in `paths` with a clone of `replacement`,
+    /// leaving the rest of the document intact. Paths that don't resolve are skipped.
+    pub fn redact_paths(&mut self, paths: &[&str], replacement: Value) {
+        for path in paths {
+            if let Some(value) = self.pointer_mut(path) {
+                *value = replacement.clone();
+            }
+        }
+    }
+}
+
+/// Retain the children of `value` that are reachable by `paths`. Returns `true` if anything was retained.
+fn retain_tokens(value: &mut Value, paths: &[&[Cow<'_, str>]]) -> bool {
+    if paths.iter().any(|path| path.is_empty()) {
+        return true;
+    }
+    // Collects the remainder of the paths that continue through the child matching `is_child`.
+    let through = |is_child: &dyn Fn(&str) -> bool| -> Vec<&[Cow<'_, str>]> {
+        paths.iter()
+            .filter(|path| is_child(&path[0]))
+            .map(|path| &path[1..])
+            .collect()
+    };
+    match value {
+        Value::Object(object) => {
+            object.retain(|key, child| {
+                let paths = through(&|token| token == key);
+                !paths.is_empty() && retain_tokens(child, &paths)
+            });
+            !object.is_empty()
+        }
+        Value::Array(array) => {
+            let mut index = 0;
+            array.retain_mut(|child| {
+                let paths = through(&|token| is_index(token, index));
+                index += 1;
+                !paths.is_empty() && retain_tokens(child, &paths)
+            });
+            !array.is_empty()
+        }
+        _ => false,
+    }
+}
+
+/// Compare `lhs` and `rhs`, ignoring the values reachable by `paths`.
+fn eq_ignoring_tokens(lhs: &Value, rhs: &Value, paths: &[&[Cow<'_, str>]]) -> bool {
+    if paths.iter().any(|path| path.is_empty()) {
+        return true;
+    }
+    if paths.is_empty() {
+        return lhs == rhs;
+    }
+    // Collects the remainder of the paths that continue through the child matching `is_child`.
+    let through = |is_child: &dyn Fn(&str) -> bool| -> Vec<&[Cow<'_, str>]> {
+        paths.iter()
+            .filter(|path| is_child(&path[0]))
+            .map(|path| &path[1..])
+            .collect()
+    };
+    // Checks if the child matching `is_child` is ignored entirely.
+    let ignored = |is_child: &dyn Fn(&str) -> bool| paths.iter().any(|path| path.len() == 1 && is_child(&path[0]));
+    match (lhs, rhs) {
+        (Value::Object(lhs), Value::Object(rhs)) => {
+            let missing = |from: &ValueMap, to: &ValueMap| from.keys()
+                .filter(|key| !to.contains_key(key.as_str()))
+                .all(|key| ignored(&|token| token == key));
+            missing(lhs, rhs) && missing(rhs, lhs) && lhs.iter().all(|(key, value)| {
+                rhs.get(key).is_none_or(|other| eq_ignoring_tokens(value, other, &through(&|token| token == key)))
+            })
+        }
+        (Value::Array(lhs), Value::Array(rhs)) => {
+            lhs.len() == rhs.len() && lhs.iter().zip(rhs).enumerate().all(|(index, (value, other))| {
+                eq_ignoring_tokens(value, other, &through(&|token| is_index(token, index)))
+            })
+        }
+        _ => lhs == rhs,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::error::ParseError;
+
+    #[test]
+    fn pointer_test() -> Result<(), ParseError> {
+        let value = Value::from_str(r#"{"a/b": [10, {"m~n": true}], "": 1}"#)?;
+        assert!(matches!(value.pointer(""), Some(Value::Object(_))));
+        assert!(matches!(value.pointer("/a~1b/1/m~0n"), Some(Value::Boolean(true))));
+        assert!(matches!(value.pointer("/"), Some(Value::Number(_))));
+        assert!(value.pointer("/a~1b/01").is_none());
+        assert!(value.pointer("/a~1b/2").is_none());
+        assert!(value.pointer("a~1b").is_none());
+        assert!(value.path_exists("/a~1b/0"));
+        assert!(!value.path_exists("/a~1b/0/x"));
+        assert!(!value.path_exists("/missing"));
+        Ok(())
+    }
+
+    #[test]
+    fn clone_at_test() -> Result<(), ParseError> {
+        let value = Value::from_str(r#"{"config": {"db": {"host": "localhost", "port": 5432}}, "other": [1, 2]}"#)?;
+        let original = value.clone();
+        let mut db = value.clone_at("/config/db").unwrap();
+        assert_eq!(db, Value::from_str(r#"{"host": "localhost", "port": 5432}"#)?);
+        db.insert("host", "remote");
+        assert!(value["config"]["db"]["host"] == "localhost");
+        assert_eq!(value, original);
+        assert!(value.clone_at("/config/missing").is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn pointer_or_insert_test() -> Result<(), ParseError> {
+        let mut value = Value::from_str(r#"{"list": [1], "flag": true}"#)?;
+        *value.pointer_or_insert("/a/b").unwrap() = Value::from(1i64);
+        assert!(matches!(value.pointer("/a/b"), Some(Value::Number(_))));
+        *value.pointer_or_insert("/list/-").unwrap() = Value::from(2i64);
+        assert!(matches!(value.pointer_or_insert("/list/2"), Some(Value::Null)));
+        assert_eq!(value["list"].len(), 3);
+        assert!(value.pointer_or_insert("/list/4").is_none());
+        assert!(value.pointer_or_insert("/flag/x").is_none());
+        assert!(value.pointer_or_insert("missing").is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn retain_paths_test() -> Result<(), ParseError> {
+        let mut value = Value::from_str(r#"
+            {
+                "user": {
+                    "id": 7,
+                    "name": "Fred",
+                    "email": "fred@example.com",
+                    "roles": ["admin", "user"]
+                },
+                "session": "abc123",
+                "items": [{"a": 1, "b": 2}, {"a": 3, "b": 4}]
+            }
+        "#)?;
+        value.retain_paths(&["/user/name", "/user/id", "/items/1/b", "/missing/path"]);
+        assert_eq!(value.len(), 2);
+        assert_eq!(value["user"].len(), 2);
+        assert!(matches!(&value["user"]["name"], Value::String(name) if name == "Fred"));
+        assert!(matches!(value["user"]["id"], Value::Number(_)));
+        assert_eq!(value["items"].len(), 1);
+        assert_eq!(value["items"][0].len(), 1);
+        assert!(value.pointer("/items/0/b").is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn eq_ignoring_test() -> Result<(), ParseError> {
+        let lhs = Value::from_str(r#"{"id": 1, "timestamp": "2024-01-01", "items": [{"a": 1, "at": 1}], "meta": {"rid": "x"}}"#)?;
+        let rhs = Value::from_str(r#"{"id": 1, "timestamp": "2024-06-30", "items": [{"a": 1, "at": 2}], "meta": {"rid": "y"}}"#)?;
+        assert!(lhs != rhs);
+        assert!(!lhs.eq_ignoring(&rhs, &["/timestamp"]));
+        assert!(lhs.eq_ignoring(&rhs, &["/timestamp", "/items/0/at", "/meta/rid"]));
+        assert!(lhs.eq_ignoring(&rhs, &["/timestamp", "/items", "/meta"]));
+        assert!(!lhs.eq_ignoring(&rhs, &["/timestamp", "/items/1/at", "/meta/rid"]));
+
+        let without = Value::from_str(r#"{"id": 1, "items": [{"a": 1, "at": 1}], "meta": {"rid": "x"}}"#)?;
+        assert!(lhs.eq_ignoring(&without, &["/timestamp"]));
+        assert!(without.eq_ignoring(&lhs, &["/timestamp"]));
+        assert!(!without.eq_ignoring(&lhs, &[]));
+        Ok(())
+    }
+
+    #[test]
+    fn redact_paths_test() -> Result<(), ParseError> {
+        let mut value = Value::from_str(r#"
+            {
+                "user": "fred",
+                "password": "hunter2",
+                "card": {"number": "4111111111111111", "expiry": "01/30"}
+            }
+        "#)?;
+        value.redact_paths(&["/password", "/card/number", "/card/cvv"], Value::from("***"));
+        assert!(matches!(&value["password"], Value::String(s) if s == "***"));
+        assert!(matches!(&value["card"]["number"], Value::String(s) if s == "***"));
+        assert!(matches!(&value["card"]["expiry"], Value::String(s) if s == "01/30"));
+        assert!(matches!(&value["user"], Value::String(s) if s == "fred"));
+        assert!(value.pointer("/card/cvv").is_none());
+        Ok(())
+    }
+}
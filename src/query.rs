@@ -0,0 +1,327 @@
+// A small query language for selecting values, based on a subset of JSONPath.
+use std::cmp::Ordering;
+
+use crate::error::QueryError;
+use crate::Value;
+
+/// A comparison operator in a filter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+/// A filter that keeps the children where the value at `path` compares true against `literal`.
+#[derive(Debug, Clone, PartialEq)]
+struct Filter {
+    path: Vec<String>,
+    operator: Operator,
+    literal: Value,
+}
+
+impl Filter {
+    fn matches(&self, value: &Value) -> bool {
+        let Some(found) = self.path.iter().try_fold(value, |value, key| value.as_object()?.get(key)) else {
+            return false;
+        };
+        let ordering = match (found, &self.literal) {
+            (Value::Number(lhs), Value::Number(rhs)) => lhs.partial_cmp(rhs),
+            (Value::String(lhs), Value::String(rhs)) => Some(lhs.cmp(rhs)),
+            (lhs, rhs) => (lhs == rhs).then_some(Ordering::Equal),
+        };
+        match self.operator {
+            Operator::Eq => ordering == Some(Ordering::Equal),
+            Operator::Ne => ordering != Some(Ordering::Equal),
+            Operator::Lt => ordering == Some(Ordering::Less),
+            Operator::Le => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+            Operator::Gt => ordering == Some(Ordering::Greater),
+            Operator::Ge => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+        }
+    }
+}
+
+/// One step of a [Query].
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Child(String),
+    Index(usize),
+    Wildcard,
+    Filter(Filter),
+}
+
+/// A parsed query. The grammar is:
+///
+/// ```text
+/// query    = ["$"] [name] segment*
+/// segment  = "." name | ".*" | "[" index "]" | "[" string "]" | "[*]"
+///          | "[?(@" ("." name)* operator literal ")]"
+/// operator = "==" | "!=" | "<" | "<=" | ">" | ">="
+/// literal  = number | string | "true" | "false" | "null"
+/// ```
+///
+/// Names can't contain whitespace or any of `.[]()=!<>`; use a quoted string in brackets
+/// (`["a b"]` or `['a b']`) for those. `*` selects every element of an array or value of an
+/// object, and a filter keeps the ones where the comparison is true. Numbers compare by value
+/// and strings by their bytes; other types can only be equal or not equal. A filter never
+/// matches if its path doesn't exist. For example, `items[?(@.age > 18)].name` selects the
+/// names of the items older than 18.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    segments: Vec<Segment>,
+}
+
+impl Query {
+    /// Parse a query. See [Query] for the grammar.
+    pub fn parse(query: &str) -> Result<Query, QueryError> {
+        let mut parser = QueryParser { source: query, index: 0 };
+        parser.eat("$");
+        let mut segments = Vec::new();
+        if !parser.is_eof() && !matches!(parser.peek(), Some(b'.' | b'[')) {
+            segments.push(Segment::Child(parser.name()?));
+        }
+        while !parser.is_eof() {
+            segments.push(parser.segment()?);
+        }
+        Ok(Query { segments })
+    }
+
+    /// Evaluate the query against `value`, returning the matches in document order.
+    pub fn evaluate<'a>(&self, value: &'a Value) -> Vec<&'a Value> {
+        self.segments.iter().fold(vec![value], |values, segment| {
+            values.into_iter().flat_map(|value| -> Vec<&'a Value> {
+                match segment {
+                    Segment::Child(key) => value.as_object().and_then(|object| object.get(key)).into_iter().collect(),
+                    Segment::Index(index) => value.as_array().and_then(|array| array.get(*index)).into_iter().collect(),
+                    Segment::Wildcard => children(value).collect(),
+                    Segment::Filter(filter) => children(value).filter(|child| filter.matches(child)).collect(),
+                }
+            }).collect()
+        })
+    }
+}
+
+/// Iterate the elements of an array or the values of an object.
+fn children(value: &Value) -> Box<dyn Iterator<Item = &Value> + '_> {
+    match value {
+        Value::Array(array) => Box::new(array.iter()),
+        Value::Object(object) => Box::new(object.values()),
+        _ => Box::new(std::iter::empty()),
+    }
+}
+
+struct QueryParser<'a> {
+    source: &'a str,
+    index: usize,
+}
+
+impl<'a> QueryParser<'a> {
+    fn is_eof(&self) -> bool {
+        self.index >= self.source.len()
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.source.as_bytes().get(self.index).copied()
+    }
+
+    fn error(&self) -> QueryError {
+        QueryError { index: self.index }
+    }
+
+    /// Advance past `token` if the remaining query starts with it.
+    fn eat(&mut self, token: &str) -> bool {
+        let found = self.source[self.index..].starts_with(token);
+        if found {
+            self.index += token.len();
+        }
+        found
+    }
+
+    fn expect(&mut self, token: &str) -> Result<(), QueryError> {
+        if self.eat(token) {
+            Ok(())
+        } else {
+            Err(self.error())
+        }
+    }
+
+    fn eat_whitespace(&mut self) {
+        while self.peek().is_some_and(|byte| byte.is_ascii_whitespace()) {
+            self.index += 1;
+        }
+    }
+
+    /// Take bytes while `predicate` is true, failing if there are none.
+    fn take_while<F: Fn(u8) -> bool>(&mut self, predicate: F) -> Result<&'a str, QueryError> {
+        let start = self.index;
+        while self.peek().is_some_and(&predicate) {
+            self.index += 1;
+        }
+        if self.index == start {
+            return Err(self.error());
+        }
+        Ok(&self.source[start..self.index])
+    }
+
+    fn name(&mut self) -> Result<String, QueryError> {
+        let name = self.take_while(|byte| !byte.is_ascii_whitespace() && !b".[]()=!<>".contains(&byte))?;
+        Ok(name.to_owned())
+    }
+
+    fn segment(&mut self) -> Result<Segment, QueryError> {
+        if self.eat(".") {
+            if self.eat("*") {
+                return Ok(Segment::Wildcard);
+            }
+            return Ok(Segment::Child(self.name()?));
+        }
+        self.expect("[")?;
+        let segment = match self.peek() {
+            Some(b'*') => {
+                self.index += 1;
+                Segment::Wildcard
+            }
+            Some(b'?') => {
+                self.index += 1;
+                Segment::Filter(self.filter()?)
+            }
+            Some(b'0'..=b'9') => {
+                let start = self.index;
+                let digits = self.take_while(|byte| byte.is_ascii_digit())?;
+                Segment::Index(digits.parse().map_err(|_| QueryError { index: start })?)
+            }
+            Some(b'"' | b'\'') => match self.literal()? {
+                Value::String(key) => Segment::Child(key),
+                _ => unreachable!(),
+            },
+            _ => return Err(self.error()),
+        };
+        self.expect("]")?;
+        Ok(segment)
+    }
+
+    fn filter(&mut self) -> Result<Filter, QueryError> {
+        self.expect("(")?;
+        self.eat_whitespace();
+        self.expect("@")?;
+        let mut path = Vec::new();
+        while self.eat(".") {
+            path.push(self.name()?);
+        }
+        self.eat_whitespace();
+        let operator = [
+            ("==", Operator::Eq),
+            ("!=", Operator::Ne),
+            ("<=", Operator::Le),
+            (">=", Operator::Ge),
+            ("<", Operator::Lt),
+            (">", Operator::Gt),
+        ].into_iter().find(|(token, _)| self.eat(token)).map(|(_, operator)| operator).ok_or(self.error())?;
+        self.eat_whitespace();
+        let literal = self.literal()?;
+        self.eat_whitespace();
+        self.expect(")")?;
+        Ok(Filter { path, operator, literal })
+    }
+
+    fn literal(&mut self) -> Result<Value, QueryError> {
+        let start = self.index;
+        let text = match self.peek() {
+            Some(b'\'') => {
+                self.index += 1;
+                let text = self.take_while(|byte| byte != b'\'').unwrap_or("");
+                self.expect("'")?;
+                return Ok(Value::String(text.to_owned()));
+            }
+            Some(b'"') => {
+                self.index += 1;
+                let mut escaped = false;
+                while let Some(byte) = self.peek() {
+                    self.index += 1;
+                    match byte {
+                        b'"' if !escaped => break,
+                        b'\\' => escaped = !escaped,
+                        _ => escaped = false,
+                    }
+                }
+                &self.source[start..self.index]
+            }
+            _ => self.take_while(|byte| byte.is_ascii_alphanumeric() || b"+-.".contains(&byte))?,
+        };
+        Value::parse(text).map_err(|_| QueryError { index: start })
+    }
+}
+
+impl Value {
+    /// Select the values matching a [Query], such as `items[?(@.age > 18)].name`.
+    /// See [Query] for the grammar.
+    pub fn query(&self, query: &str) -> Result<Vec<&Value>, QueryError> {
+        Ok(Query::parse(query)?.evaluate(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::error::ParseError;
+
+    fn document() -> Result<Value, ParseError> {
+        Value::from_str(r#"
+            {
+                "store": {"name": "Corner", "open": true},
+                "items": [
+                    {"name": "Fred", "age": 30, "tags": ["a", "b"]},
+                    {"name": "George", "age": 12},
+                    {"name": "Ron", "age": 18.5, "info": {"role": "admin"}},
+                    {"title": "no age"}
+                ]
+            }
+        "#)
+    }
+
+    #[test]
+    fn child_test() -> Result<(), Box<dyn std::error::Error>> {
+        let value = document()?;
+        assert_eq!(value.query("store.name")?, [&Value::from("Corner")]);
+        assert_eq!(value.query("$.store['name']")?, [&Value::from("Corner")]);
+        assert_eq!(value.query("items[0].tags[1]")?, [&Value::from("b")]);
+        assert_eq!(value.query("$")?, [&value]);
+        assert!(value.query("items[9].name")?.is_empty());
+        assert!(value.query("store.missing")?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn wildcard_test() -> Result<(), Box<dyn std::error::Error>> {
+        let value = document()?;
+        let names = value.query("items[*].name")?;
+        assert_eq!(names, [&Value::from("Fred"), &Value::from("George"), &Value::from("Ron")]);
+        assert_eq!(value.query("items.*.tags.*")?.len(), 2);
+        assert_eq!(value.query("store.*")?.len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn filter_test() -> Result<(), Box<dyn std::error::Error>> {
+        let value = document()?;
+        assert_eq!(value.query("items[?(@.age > 18)].name")?, [&Value::from("Fred"), &Value::from("Ron")]);
+        assert_eq!(value.query("items[?(@.age <= 18)].name")?, [&Value::from("George")]);
+        assert_eq!(value.query("items[?( @.age == 30 )].name")?, [&Value::from("Fred")]);
+        assert_eq!(value.query(r#"items[?(@.name != "Fred")].age"#)?.len(), 2);
+        assert_eq!(value.query("items[?(@.info.role == 'admin')].name")?, [&Value::from("Ron")]);
+        assert!(value.query("items[?(@.age > 'a')]")?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_query_test() {
+        for (query, index) in [("items[", 6), ("items[?(@.age >)]", 15), ("items[?(@.age ~ 1)]", 14), ("a..b", 2), ("items[0", 7)] {
+            assert_eq!(Query::parse(query).unwrap_err().index, index, "{query}");
+        }
+    }
+}
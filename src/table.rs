@@ -0,0 +1,147 @@
+use crate::Value;
+
+impl Value {
+    /// Checks if this [Value] has the same shape as `other`: both are the same JSON type,
+    /// and if they are objects, they have the same keys and the values of each key have the
+    /// same shape. The elements of arrays are not compared.
+    pub fn same_shape(&self, other: &Value) -> bool {
+        match (self, other) {
+            (Value::Object(lhs), Value::Object(rhs)) => {
+                lhs.len() == rhs.len()
+                && lhs.iter().all(|(key, value)| rhs.get(key).is_some_and(|other| value.same_shape(other)))
+            }
+            _ => self.type_name() == other.type_name(),
+        }
+    }
+
+    /// Checks if this [Value] is a [Value::Array] whose elements all have the same shape as
+    /// the first element (see [Value::same_shape]). Empty arrays are homogeneous.
+    pub fn is_homogeneous_array(&self) -> bool {
+        let Value::Array(array) = self else {
+            return false;
+        };
+        match array.split_first() {
+            Some((first, rest)) => rest.iter().all(|value| first.same_shape(value)),
+            None => true,
+        }
+    }
+
+    /// Get the `key` field of every object in a [Value::Array], in order, like selecting a
+    /// column. Elements that are not objects or are missing the key are skipped. Returns an
+    /// empty [Vec] if this is not an array.
+    pub fn pluck(&self, key: &str) -> Vec<&Value> {
+        let Value::Array(array) = self else {
+            return Vec::new();
+        };
+        array.iter().filter_map(|element| element.as_object()?.get(key)).collect()
+    }
+
+    /// Convert a [Value::Array] of [Value::Object]s into a table. The headers are the union
+    /// of the keys of every object, in the order that they're first seen, and each row has
+    /// the values of one object aligned to the headers. Missing keys become [Value::Null].
+    ///
+    /// Returns `None` if this is not an array, or if any element is not an object.
+    pub fn to_rows(&self) -> Option<(Vec<String>, Vec<Vec<Value>>)> {
+        let records = self.as_array()?
+            .iter()
+            .map(Value::as_object)
+            .collect::<Option<Vec<_>>>()?;
+        let mut headers: Vec<String> = Vec::new();
+        for record in &records {
+            for key in record.keys() {
+                if !headers.contains(key) {
+                    headers.push(key.clone());
+                }
+            }
+        }
+        let rows = records.iter().map(|record| {
+            headers.iter()
+                .map(|header| record.get(header).cloned().unwrap_or(Value::Null))
+                .collect()
+        }).collect();
+        Some((headers, rows))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::error::ParseError;
+
+    #[test]
+    fn is_homogeneous_array_test() -> Result<(), ParseError> {
+        let homogeneous = [
+            "[]",
+            "[1, 2.5, 3]",
+            r#"[{"id": 1, "tags": ["a"]}, {"tags": [], "id": 2}]"#,
+            r#"[{"a": {"b": null}}, {"a": {"b": null}}]"#,
+        ];
+        for source in homogeneous {
+            assert!(Value::from_str(source)?.is_homogeneous_array(), "{source}");
+        }
+        let heterogeneous = [
+            r#"[1, "2"]"#,
+            r#"[{"id": 1}, {"id": 2, "name": "extra"}]"#,
+            r#"[{"id": 1}, {"key": 1}]"#,
+            r#"[{"id": 1}, {"id": "1"}]"#,
+            r#"[{"a": {"b": 1}}, {"a": {"c": 1}}]"#,
+            r#"{"not": "an array"}"#,
+        ];
+        for source in heterogeneous {
+            assert!(!Value::from_str(source)?.is_homogeneous_array(), "{source}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn pluck_test() -> Result<(), ParseError> {
+        let value = Value::from_str(r#"
+            [
+                {"id": 1, "name": "Fred"},
+                {"id": 2},
+                "not a record",
+                {"id": 3, "name": null},
+                {"id": 4, "name": "George"}
+            ]
+        "#)?;
+        let names = value.pluck("name");
+        assert_eq!(names.len(), 3);
+        assert!(*names[0] == "Fred");
+        assert!(matches!(names[1], Value::Null));
+        assert!(*names[2] == "George");
+        assert!(value[0].pluck("name").is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn to_rows_test() -> Result<(), ParseError> {
+        let value = Value::from_str(r#"
+            [
+                {"id": 1, "name": "Fred"},
+                {"id": 2, "email": "george@example.com"},
+                {"name": "Ron", "id": 3}
+            ]
+        "#)?;
+        let (headers, rows) = value.to_rows().unwrap();
+        let mut sorted = headers.clone();
+        sorted.sort();
+        assert_eq!(sorted, ["email", "id", "name"]);
+        assert_eq!(rows.len(), 3);
+        for (row, record) in rows.iter().zip(value.as_array().unwrap()) {
+            assert_eq!(row.len(), headers.len());
+            for (header, cell) in headers.iter().zip(row) {
+                assert_eq!(cell, &record[header.as_str()]);
+            }
+        }
+        let email = headers.iter().position(|header| header == "email").unwrap();
+        assert!(matches!(rows[0][email], Value::Null));
+        assert!(rows[1][email] == "george@example.com");
+
+        assert_eq!(Value::from_str("[]")?.to_rows(), Some((Vec::new(), Vec::new())));
+        assert!(Value::from_str(r#"[{"id": 1}, 2]"#)?.to_rows().is_none());
+        assert!(Value::from_str(r#"{"id": 1}"#)?.to_rows().is_none());
+        Ok(())
+    }
+}
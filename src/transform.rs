@@ -0,0 +1,417 @@
+use std::cmp::Ordering;
+
+use crate::{remove_entry, Number, Value, ValueMap};
+
+impl Value {
+    /// Checks if this [Value] is an empty [Value::Array] or [Value::Object].
+    fn is_empty_container(&self) -> bool {
+        match self {
+            Value::Array(array) => array.is_empty(),
+            Value::Object(object) => object.is_empty(),
+            _ => false,
+        }
+    }
+
+    fn compact_impl(&mut self, remove_nulls: bool) {
+        let remove = |value: &Value| {
+            value.is_empty_container() || (remove_nulls && matches!(value, Value::Null))
+        };
+        // Children are compacted before checking them so that containers that
+        // became empty are removed as well.
+        match self {
+            Value::Array(array) => array.retain_mut(|value| {
+                value.compact_impl(remove_nulls);
+                !remove(value)
+            }),
+            Value::Object(object) => object.retain(|_, value| {
+                value.compact_impl(remove_nulls);
+                !remove(value)
+            }),
+            _ => (),
+        }
+    }
+
+    /// Recursively remove object entries and array elements that are empty [Value::Array]s
+    /// or [Value::Object]s, including containers that become empty by doing so.
+    pub fn compact(&mut self) {
+        self.compact_impl(false);
+    }
+
+    /// Like [Value::compact], but also removes object entries and array elements that are [Value::Null].
+    pub fn compact_nulls(&mut self) {
+        self.compact_impl(true);
+    }
+
+    /// Reorder the keys of a [Value::Object] to follow `order`. Keys that aren't in `order`
+    /// are removed if `drop_extras` is true, otherwise they are moved after the ordered keys
+    /// and keep their relative order. Keys in `order` that don't exist are skipped.
+    ///
+    /// Key order is only kept with the `preserve_order` feature, but `drop_extras` works either way.
+    /// Does nothing if the [Value] is not a [Value::Object].
+    pub fn reorder_keys(&mut self, order: &[&str], drop_extras: bool) {
+        let Value::Object(object) = self else {
+            return;
+        };
+        let mut remaining = std::mem::take(object);
+        let mut reordered = ValueMap::with_capacity(remaining.len());
+        for &key in order {
+            if let Some(value) = remove_entry(&mut remaining, key) {
+                reordered.insert(key.to_owned(), value);
+            }
+        }
+        if !drop_extras {
+            reordered.extend(remaining);
+        }
+        *object = reordered;
+    }
+
+    /// Rename the key `from` to `to` in this [Value::Object], keeping its value. With the
+    /// `preserve_order` feature the entry keeps its position. An existing entry under `to`
+    /// is replaced.
+    /// Returns `false` if this [Value] is not a [Value::Object] or `from` is not present.
+    pub fn rename_key(&mut self, from: &str, to: &str) -> bool {
+        let Value::Object(object) = self else {
+            return false;
+        };
+        #[cfg(feature = "preserve_order")]
+        {
+            let Some(mut index) = object.get_index_of(from) else {
+                return false;
+            };
+            if from == to {
+                return true;
+            }
+            let (_, value) = object.shift_remove_index(index).expect("index is in bounds");
+            if let Some((replaced, _, _)) = object.shift_remove_full(to) {
+                if replaced < index {
+                    index -= 1;
+                }
+            }
+            object.shift_insert(index, to.to_owned(), value);
+            true
+        }
+        #[cfg(not(feature = "preserve_order"))]
+        {
+            let Some(value) = remove_entry(object, from) else {
+                return false;
+            };
+            object.insert(to.to_owned(), value);
+            true
+        }
+    }
+
+    /// Create a new [Value::Object] from the entries of this object whose keys start with
+    /// `prefix`, with the prefix removed from the keys. Other entries are left out.
+    /// Returns an empty object if this [Value] is not a [Value::Object].
+    pub fn strip_prefix_keys(&self, prefix: &str) -> Value {
+        let Value::Object(object) = self else {
+            return Value::Object(ValueMap::new());
+        };
+        Value::Object(object.iter().filter_map(|(key, value)| {
+            Some((key.strip_prefix(prefix)?.to_owned(), value.clone()))
+        }).collect())
+    }
+
+    /// Stably sort every [Value::Array] in the document whose elements are all
+    /// [Value::Object]s by the value at the JSON Pointer `pointer`, relative to each element
+    /// (e.g. `"/id"`).
+    ///
+    /// Values of the same type are compared naturally (numbers by value, strings by their
+    /// bytes, `false` before `true`). Values of different types are ordered null, boolean,
+    /// number, string, array, object, and arrays and objects compare as equal to each other.
+    /// Elements that are missing the key sort last.
+    pub fn sort_records_by(&mut self, pointer: &str) {
+        self.for_each_mut(&mut |value| {
+            let Value::Array(array) = value else {
+                return;
+            };
+            if !array.iter().all(|element| matches!(element, Value::Object(_))) {
+                return;
+            }
+            array.sort_by(|a, b| match (a.pointer(pointer), b.pointer(pointer)) {
+                (Some(a), Some(b)) => compare_sort_keys(a, b),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            });
+        });
+    }
+
+    /// Rewrite every object key with `key_fn` and every [Value] with `value_fn`, such as to
+    /// migrate a document to a new schema. The document is transformed bottom-up, so when
+    /// `value_fn` is called on an array or object, its children (and their keys) have
+    /// already been transformed. If two keys of an object become the same, the last one wins.
+    pub fn transform<K, V>(&mut self, key_fn: K, value_fn: V)
+    where
+        K: Fn(&str) -> String + Copy,
+        V: Fn(&mut Value) + Copy,
+    {
+        match self {
+            Value::Array(array) => array.iter_mut().for_each(|value| value.transform(key_fn, value_fn)),
+            Value::Object(object) => {
+                *object = std::mem::take(object).into_iter().map(|(key, mut value)| {
+                    value.transform(key_fn, value_fn);
+                    (key_fn(&key), value)
+                }).collect();
+            }
+            _ => (),
+        }
+        value_fn(self);
+    }
+
+    /// Call `f` on every [Value] in the document, parents before their children.
+    fn for_each_mut<F: FnMut(&mut Value)>(&mut self, f: &mut F) {
+        f(self);
+        match self {
+            Value::Array(array) => array.iter_mut().for_each(|value| value.for_each_mut(f)),
+            Value::Object(object) => object.values_mut().for_each(|value| value.for_each_mut(f)),
+            _ => (),
+        }
+    }
+
+    /// Call `f` on every [Value::String] in the document (object keys are not included).
+    pub fn for_each_string_mut<F: FnMut(&mut String)>(&mut self, mut f: F) {
+        self.for_each_mut(&mut |value| {
+            if let Value::String(string) = value {
+                f(string);
+            }
+        });
+    }
+
+    /// Replace every `${NAME}` placeholder in the [Value::String]s of the document (object
+    /// keys are not included) with `resolve(NAME)`, such as to fill in a configuration
+    /// template from environment variables. Placeholders that `resolve` returns `None` for,
+    /// and a `${` without a closing `}`, are left as they are. Replacements are not scanned
+    /// for placeholders again.
+    pub fn substitute<F: Fn(&str) -> Option<String>>(&mut self, resolve: F) {
+        self.for_each_string_mut(|string| {
+            if !string.contains("${") {
+                return;
+            }
+            let mut output = String::with_capacity(string.len());
+            let mut rest = string.as_str();
+            while let Some(start) = rest.find("${") {
+                let Some(len) = rest[start + 2..].find('}') else {
+                    break;
+                };
+                let end = start + 2 + len + 1;
+                output.push_str(&rest[..start]);
+                match resolve(&rest[start + 2..end - 1]) {
+                    Some(replacement) => output.push_str(&replacement),
+                    None => output.push_str(&rest[start..end]),
+                }
+                rest = &rest[end..];
+            }
+            output.push_str(rest);
+            *string = output;
+        });
+    }
+
+    /// Call `f` on every [Value::Number] in the document.
+    pub fn for_each_number_mut<F: FnMut(&mut Number)>(&mut self, mut f: F) {
+        self.for_each_mut(&mut |value| {
+            if let Value::Number(number) = value {
+                f(number);
+            }
+        });
+    }
+}
+
+/// The ordering used by [Value::sort_records_by].
+fn compare_sort_keys(a: &Value, b: &Value) -> Ordering {
+    fn rank(value: &Value) -> u8 {
+        match value {
+            Value::Null => 0,
+            Value::Boolean(_) => 1,
+            Value::Number(_) => 2,
+            Value::String(_) => 3,
+            Value::Array(_) => 4,
+            Value::Object(_) => 5,
+        }
+    }
+    match (a, b) {
+        (Value::Boolean(a), Value::Boolean(b)) => a.cmp(b),
+        (Value::Number(a), Value::Number(b)) => a.cmp(b),
+        (Value::String(a), Value::String(b)) => a.cmp(b),
+        _ => rank(a).cmp(&rank(b)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::error::ParseError;
+
+    #[test]
+    fn compact_test() -> Result<(), ParseError> {
+        let source = r#"
+            {
+                "keep": [1, [], {}],
+                "empty": {"nested": {"list": [[], {}]}},
+                "nulls": [null, {"a": null}],
+                "null": null
+            }
+        "#;
+        let mut value = Value::from_str(source)?;
+        value.compact();
+        assert_eq!(value, Value::from_str(r#"{"keep": [1], "nulls": [null, {"a": null}], "null": null}"#)?);
+
+        let mut value = Value::from_str(source)?;
+        value.compact_nulls();
+        assert_eq!(value, Value::from_str(r#"{"keep": [1]}"#)?);
+        Ok(())
+    }
+
+    #[test]
+    fn for_each_leaf_mut_test() -> Result<(), ParseError> {
+        let mut value = Value::from_str(r#"
+            {
+                "  padded  ": "  name ",
+                "list": [" a", 3.14159, {"b": "b  ", "c": 2.71828}],
+                "count": 7
+            }
+        "#)?;
+        value.for_each_string_mut(|string| *string = string.trim().to_owned());
+        value.for_each_number_mut(|number| {
+            if let Number::Float(float) = number {
+                *float = (*float * 100.0).round() / 100.0;
+            }
+        });
+        let expected = Value::from_str(r#"
+            {
+                "  padded  ": "name",
+                "list": ["a", 3.14, {"b": "b", "c": 2.72}],
+                "count": 7
+            }
+        "#)?;
+        assert_eq!(value, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn substitute_test() -> Result<(), ParseError> {
+        let mut value = Value::from_str(r#"
+            {
+                "url": "http://${HOST}:${PORT}/",
+                "nested": {"list": ["${USER}", "${UNKNOWN} stays", "no placeholders", "${HOST"]},
+                "${HOST}": 1
+            }
+        "#)?;
+        value.substitute(|name| match name {
+            "HOST" => Some("localhost".to_owned()),
+            "PORT" => Some("8080".to_owned()),
+            "USER" => Some("${HOST}".to_owned()),
+            _ => None,
+        });
+        assert_eq!(value, Value::from_str(r#"
+            {
+                "url": "http://localhost:8080/",
+                "nested": {"list": ["${HOST}", "${UNKNOWN} stays", "no placeholders", "${HOST"]},
+                "${HOST}": 1
+            }
+        "#)?);
+        Ok(())
+    }
+
+    #[test]
+    fn reorder_keys_test() -> Result<(), ParseError> {
+        let source = r#"{"extra1": 0, "c": 3, "a": 1, "extra2": 0, "b": 2}"#;
+        let mut value = Value::from_str(source)?;
+        value.reorder_keys(&["a", "b", "missing", "c"], true);
+        assert_eq!(value, Value::from_str(r#"{"a": 1, "b": 2, "c": 3}"#)?);
+        #[cfg(feature = "preserve_order")]
+        assert_eq!(value.to_string(), r#"{"a":1,"b":2,"c":3}"#);
+
+        let mut value = Value::from_str(source)?;
+        value.reorder_keys(&["a", "b", "c"], false);
+        assert_eq!(value, Value::from_str(source)?);
+        #[cfg(feature = "preserve_order")]
+        assert_eq!(value.to_string(), r#"{"a":1,"b":2,"c":3,"extra1":0,"extra2":0}"#);
+        Ok(())
+    }
+
+    #[test]
+    fn rename_key_test() -> Result<(), ParseError> {
+        let mut value = Value::from_str(r#"{"a": 1, "b": 2, "c": 3}"#)?;
+        assert!(value.rename_key("b", "middle"));
+        assert_eq!(value, Value::from_str(r#"{"a": 1, "middle": 2, "c": 3}"#)?);
+        #[cfg(feature = "preserve_order")]
+        assert_eq!(value.to_string(), r#"{"a":1,"middle":2,"c":3}"#);
+
+        assert!(value.rename_key("middle", "a"));
+        assert_eq!(value, Value::from_str(r#"{"a": 2, "c": 3}"#)?);
+        #[cfg(feature = "preserve_order")]
+        assert_eq!(value.to_string(), r#"{"a":2,"c":3}"#);
+
+        assert!(value.rename_key("c", "c"));
+        assert!(!value.rename_key("missing", "z"));
+        assert!(!Value::from("a").rename_key("a", "b"));
+        Ok(())
+    }
+
+    #[test]
+    fn strip_prefix_keys_test() -> Result<(), ParseError> {
+        let value = Value::from_str(r#"
+            {"DB_HOST": "localhost", "DB_PORT": 5432, "APP_NAME": "bourne", "DB": "ignored"}
+        "#)?;
+        let db = value.strip_prefix_keys("DB_");
+        assert_eq!(db, Value::from_str(r#"{"HOST": "localhost", "PORT": 5432}"#)?);
+        assert_eq!(value.strip_prefix_keys("NONE_"), Value::Object(ValueMap::new()));
+        assert_eq!(Value::from("DB_HOST").strip_prefix_keys("DB_"), Value::Object(ValueMap::new()));
+        Ok(())
+    }
+
+    #[test]
+    fn sort_records_by_test() -> Result<(), ParseError> {
+        let mut value = Value::from_str(r#"
+            {
+                "users": [{"id": 3}, {"name": "no id"}, {"id": 1}, {"id": 2, "tags": [{"id": 9}, {"id": 8}]}],
+                "mixed": [{"id": 2}, 1, {"id": 1}]
+            }
+        "#)?;
+        value.sort_records_by("/id");
+        let expected = Value::from_str(r#"
+            {
+                "users": [{"id": 1}, {"id": 2, "tags": [{"id": 8}, {"id": 9}]}, {"id": 3}, {"name": "no id"}],
+                "mixed": [{"id": 2}, 1, {"id": 1}]
+            }
+        "#)?;
+        assert_eq!(value, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn transform_test() -> Result<(), ParseError> {
+        let mut value = Value::from_str(r#"
+            {
+                "name": "root",
+                "children": [
+                    {"name": "fred", "age": 30},
+                    {"name": "george", "pets": [{"name": "rex"}]}
+                ]
+            }
+        "#)?;
+        value.transform(
+            |key| if key == "name" { String::from("label") } else { key.to_owned() },
+            |value| {
+                // Keys are already renamed when the parent object is transformed.
+                if let Some(Value::String(label)) = value.get_mut("label") {
+                    *label = label.to_uppercase();
+                }
+            },
+        );
+        let expected = Value::from_str(r#"
+            {
+                "label": "ROOT",
+                "children": [
+                    {"label": "FRED", "age": 30},
+                    {"label": "GEORGE", "pets": [{"label": "REX"}]}
+                ]
+            }
+        "#)?;
+        assert_eq!(value, expected);
+        Ok(())
+    }
+}
@@ -0,0 +1,78 @@
+use crate::Value;
+
+impl Value {
+    /// Fold over every leaf [Value] (every value that is not an array or object) in
+    /// document order, such as to sum all of the numbers in a document.
+    pub fn fold<B, F: FnMut(B, &Value) -> B>(&self, init: B, mut f: F) -> B {
+        fn fold_impl<B, F: FnMut(B, &Value) -> B>(value: &Value, init: B, f: &mut F) -> B {
+            match value {
+                Value::Array(array) => array.iter().fold(init, |acc, value| fold_impl(value, acc, f)),
+                Value::Object(object) => object.values().fold(init, |acc, value| fold_impl(value, acc, f)),
+                leaf => f(init, leaf),
+            }
+        }
+        fold_impl(self, init, &mut f)
+    }
+
+    /// Count every [Value] in the document for which `predicate` returns `true`, including
+    /// this one and the arrays and objects themselves, not just the leaves.
+    pub fn count_where<F: Fn(&Value) -> bool>(&self, predicate: F) -> usize {
+        fn count_impl<F: Fn(&Value) -> bool>(value: &Value, predicate: &F) -> usize {
+            let children = match value {
+                Value::Array(array) => array.iter().map(|value| count_impl(value, predicate)).sum(),
+                Value::Object(object) => object.values().map(|value| count_impl(value, predicate)).sum(),
+                _ => 0,
+            };
+            children + usize::from(predicate(value))
+        }
+        count_impl(self, &predicate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::error::ParseError;
+
+    #[test]
+    fn fold_test() -> Result<(), ParseError> {
+        let value = Value::from_str(r#"
+            {
+                "a": 1,
+                "b": [2, 3.5, "4", {"c": [5]}],
+                "d": {"e": null, "f": true, "g": []}
+            }
+        "#)?;
+        let sum = value.fold(0.0, |sum, value| sum + value.as_f64().unwrap_or(0.0));
+        assert_eq!(sum, 11.5);
+        assert_eq!(value.fold(0, |count, _| count + 1), 7);
+        let strings = Value::from_str(r#"["a", ["b", ["c"]], "d"]"#)?;
+        let joined = strings.fold(String::new(), |mut joined, value| {
+            if let Value::String(string) = value {
+                joined.push_str(string);
+            }
+            joined
+        });
+        assert_eq!(joined, "abcd");
+        Ok(())
+    }
+
+    #[test]
+    fn count_where_test() -> Result<(), ParseError> {
+        let value = Value::from_str(r#"
+            {
+                "a": 150,
+                "b": [2, 300.5, "400", {"c": [101]}],
+                "d": {"e": null, "f": 100, "g": []}
+            }
+        "#)?;
+        let large = value.count_where(|value| value.as_f64().is_some_and(|number| number > 100.0));
+        assert_eq!(large, 3);
+        assert_eq!(value.count_where(|value| matches!(value, Value::Array(_))), 3);
+        assert_eq!(value.count_where(|_| true), 13);
+        assert_eq!(Value::Null.count_where(|value| matches!(value, Value::Null)), 1);
+        Ok(())
+    }
+}
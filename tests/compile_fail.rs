@@ -0,0 +1,49 @@
+// Checks that each program in `tests/ui` fails to compile with the errors in the `.stderr`
+// file next to it, like trybuild does. Each program is built as its own crate depending on
+// bourne with the `deny_duplicate_keys` feature.
+#![cfg(feature = "deny_duplicate_keys")]
+use std::path::Path;
+use std::process::Command;
+
+/// Build `tests/ui/{name}.rs` and return its errors, with paths relative to the crate root.
+fn compile_errors(name: &str) -> String {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let dir = root.join("target").join("ui").join(name);
+    std::fs::create_dir_all(&dir).unwrap();
+    let manifest = format!(
+        "[package]\nname = \"ui-{name}\"\nversion = \"0.0.0\"\nedition = \"2021\"\n\n\
+        [[bin]]\nname = \"{name}\"\npath = {source:?}\n\n\
+        [dependencies]\nbourne = {{ path = {root:?}, features = [\"deny_duplicate_keys\"] }}\n\n\
+        [workspace]\n",
+        source = root.join("tests").join("ui").join(format!("{name}.rs")),
+    );
+    std::fs::write(dir.join("Cargo.toml"), manifest).unwrap();
+    // Use the same dependency versions as the crate itself.
+    if root.join("Cargo.lock").exists() {
+        std::fs::copy(root.join("Cargo.lock"), dir.join("Cargo.lock")).unwrap();
+    }
+    let output = Command::new(env!("CARGO"))
+        .args(["check", "--quiet", "--offline", "--color", "never"])
+        .current_dir(&dir)
+        .env("CARGO_TARGET_DIR", root.join("target").join("ui").join("target"))
+        .output()
+        .unwrap();
+    assert!(!output.status.success(), "tests/ui/{name}.rs compiled");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let prefix = format!("{}/", root.display());
+    stderr.lines()
+        .take_while(|line| !line.starts_with("error: could not compile"))
+        .map(|line| line.replace(&prefix, ""))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn check(name: &str) {
+    let expected = std::fs::read_to_string(format!("{}/tests/ui/{name}.stderr", env!("CARGO_MANIFEST_DIR"))).unwrap();
+    assert_eq!(compile_errors(name).trim_end(), expected.trim_end());
+}
+
+#[test]
+fn duplicate_key_test() {
+    check("duplicate_key");
+}
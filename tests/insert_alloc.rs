@@ -0,0 +1,43 @@
+// Counts allocations to check that updating an existing key through `Value::insert` with a
+// `&str` key doesn't allocate. This needs its own binary because the allocator is global.
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use bourne::Value;
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+#[test]
+fn insert_existing_key_does_not_allocate() {
+    let mut value = Value::Null;
+    value.insert("count", 0i64);
+    value.insert("name", true);
+
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    for count in 1..100i64 {
+        value.insert("count", count);
+    }
+    let previous = value.insert("name", false);
+    assert_eq!(ALLOCATIONS.load(Ordering::Relaxed), before);
+
+    assert!(matches!(previous, Some(Value::Boolean(true))));
+    assert!(value["count"] == 99);
+    value.insert("new", 1i64);
+    assert!(ALLOCATIONS.load(Ordering::Relaxed) > before);
+}
@@ -0,0 +1,9 @@
+use bourne::json;
+
+fn main() {
+    let _ = json!({
+        "name": "bourne",
+        "version": 1,
+        "name": "bournemacro"
+    });
+}